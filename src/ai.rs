@@ -0,0 +1,557 @@
+//! # AI
+//!
+//! Computer players for Super Tic-Tac-Toe.
+
+use super::{Board, Player, Position, Status, STTT};
+use super::board::LINES;
+
+/// Score of a won game. Quicker wins score higher.
+const WIN: i32 = 1_000_000;
+
+/// Value of winning the center board, which takes part in four metaboard lines
+pub const CENTER_WEIGHT: i32 = 4;
+/// Value of winning a corner board, which takes part in three metaboard lines
+pub const CORNER_WEIGHT: i32 = 3;
+/// Value of winning an edge board, which takes part in two metaboard lines
+pub const EDGE_WEIGHT: i32 = 2;
+/// Bonus for two boards in a metaboard line whose third board is still open
+pub const THREAT_BONUS: i32 = 5;
+
+/// Scores the position from X's perspective: positive favors X, negative
+/// favors O.
+///
+/// Each board won is worth [`CENTER_WEIGHT`], [`CORNER_WEIGHT`] or
+/// [`EDGE_WEIGHT`] depending on its place in the metaboard, and each
+/// metaboard line that a player could complete with one more board is worth
+/// [`THREAT_BONUS`]. Won games score `±1_000_000` and tied games `0`.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, ai};
+///
+/// let code = |cells: &[(usize, char)]| {
+///     let mut tiles = vec!['.'; 81];
+///     for &(i, c) in cells {
+///         tiles[i] = c;
+///     }
+///     STTT::from_code(&format!("{}X", tiles.into_iter().collect::<String>())).unwrap()
+/// };
+///
+/// assert_eq!(ai::evaluate(&STTT::new()), 0);
+///
+/// // X won board 0, O won board 8
+/// let even = code(&[(0, 'X'), (1, 'X'), (2, 'X'), (72, 'O'), (73, 'O'), (74, 'O')]);
+/// assert_eq!(ai::evaluate(&even), 0);
+///
+/// // X won boards 0 and 4, threatening the diagonal
+/// let ahead = code(&[(0, 'X'), (1, 'X'), (2, 'X'), (36, 'X'), (37, 'X'), (38, 'X'),
+///                    (45, 'O'), (46, 'O'), (50, 'O'), (51, 'O'), (65, 'O'), (66, 'O')]);
+/// assert!(ai::evaluate(&ahead) > 0);
+/// ```
+pub fn evaluate(game: &STTT) -> i32 {
+    match game.status() {
+        Status::Winner(Player::X) => return WIN,
+        Status::Winner(Player::O) => return -WIN,
+        Status::Tie => return 0,
+        Status::InProgress => {},
+    }
+
+    let board = game.board_ref();
+    let metaboard = board.metaboard();
+    let sign = |player| if player == Player::X { 1 } else { -1 };
+
+    let mut score = 0;
+
+    for (board_idx, owner) in metaboard.iter().enumerate() {
+        if let Some(player) = *owner {
+            let weight = match board_idx {
+                4 => CENTER_WEIGHT,
+                0 | 2 | 6 | 8 => CORNER_WEIGHT,
+                _ => EDGE_WEIGHT,
+            };
+            score += sign(player) * weight;
+        }
+    }
+
+    for line in LINES.iter() {
+        for &player in [Player::X, Player::O].iter() {
+            let owned = line.iter().filter(|&&i| metaboard[i] == Some(player)).count();
+            let open = line.iter().filter(|&&i| board.is_open(i)).count();
+            if owned == 2 && open == 1 {
+                score += sign(player) * THREAT_BONUS;
+            }
+        }
+    }
+
+    score
+}
+
+/// Returns the positions in the currently valid boards where `player` would
+/// win a small board, in absolute position order.
+///
+/// Whose turn it is doesn't matter, so this also shows the opponent's
+/// threats. If the game is over, there are none.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, Player, Position, ai};
+///
+/// let mut game = STTT::new();
+/// for &pos in &[36, 4, 37, 13] {
+///     game.play_move(Position::from_absolute(pos).unwrap()).unwrap();
+/// }
+///
+/// // X must play in board 4, where it has the first two tiles of the top row
+/// assert_eq!(ai::threats(&game, Player::X), vec![Position::from_absolute(38).unwrap()]);
+/// assert_eq!(ai::threats(&game, Player::O), vec![]);
+/// ```
+pub fn threats(game: &STTT, player: Player) -> Vec<Position> {
+    if game.is_over() {
+        return Vec::new();
+    }
+
+    let board = game.board_ref();
+    let mut res = Vec::new();
+
+    for board_idx in game.valid_boards() {
+        let small_board = board.sub_board(board_idx);
+
+        for tile_idx in board.available_cells(board_idx) {
+            let mut after = small_board;
+            after[tile_idx] = Some(player);
+            if Board::check_winner(&after) == Some(player) {
+                res.push(Position::from_relative(board_idx, tile_idx).unwrap());
+            }
+        }
+    }
+
+    res
+}
+
+/// Returns the moves that win the game for the next player, in absolute
+/// position order: those winning a small board that completes a metaboard
+/// line. With `RuleSet::misere` there are none, since the board would go to
+/// the opponent.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, Position, ai};
+///
+/// // X owns boards 0 and 1, and can take board 2 at 20
+/// let game = STTT::from_code(&format!("XXX......XXX......XX{}X", ".".repeat(61))).unwrap();
+/// assert_eq!(ai::winning_moves(&game), vec![Position::from_absolute(20).unwrap()]);
+/// ```
+pub fn winning_moves(game: &STTT) -> Vec<Position> {
+    let player = game.player();
+    let owner = if game.rules().misere { player.opponent() } else { player };
+    let metaboard = game.board_ref().metaboard();
+
+    threats(game, player).into_iter()
+        .filter(|position| {
+            let mut after = metaboard;
+            after[position.board_idx()] = Some(owner);
+            Board::check_winner(&after) == Some(player)
+        })
+        .collect()
+}
+
+/// Outcome of a game tree search
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Search {
+    /// Best move found, or `None` if there are no legal moves
+    pub best: Option<Position>,
+    /// Number of positions visited
+    pub nodes: usize,
+}
+
+/// Returns the best move for the next player, searching `depth` moves ahead
+/// with minimax and alpha-beta pruning.
+///
+/// Won and lost games score `+∞` and `−∞` (quicker wins and slower losses
+/// being preferred). Other positions score the number of boards won by the
+/// player minus the boards won by the opponent. Among equally good moves,
+/// the one with the lowest absolute position is returned.
+///
+/// A `depth` of `0` searches like `1`, since picking a move takes looking
+/// at least one move ahead.
+///
+/// Returns `None` only if there are no legal moves: when the game is over,
+/// or while a board choice is pending (see `STTT::pending_choice`), which
+/// [`best_board`] makes instead.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, Position, ai};
+///
+/// // X wins board 2, and with it the top row of the metaboard
+/// let game = STTT::from_code(&format!("XXX......XXX......XX{}X", ".".repeat(61))).unwrap();
+/// assert_eq!(ai::best_move(&game, 2), Some(Position::from_absolute(20).unwrap()));
+/// ```
+pub fn best_move(game: &STTT, depth: usize) -> Option<Position> {
+    search(game, depth, true).best
+}
+
+/// Searches `depth` moves ahead like [`best_move`], optionally without
+/// alpha-beta pruning, and reports how many positions were visited.
+///
+/// Pruning never changes the chosen move, only the amount of work.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, ai};
+///
+/// let pruned = ai::search(&STTT::new(), 2, true);
+/// let full = ai::search(&STTT::new(), 2, false);
+/// assert_eq!(pruned.best, full.best);
+/// assert!(pruned.nodes < full.nodes);
+/// ```
+pub fn search(game: &STTT, depth: usize, pruning: bool) -> Search {
+    let depth = depth.max(1);
+    let mut nodes = 1;
+    let moves = game.valid_moves().into_iter().map(Action::Play).collect();
+
+    let best = root(game, game.player(), moves, depth, pruning, &mut nodes)
+        .map(|action| match action {
+            Action::Play(position) => position,
+            Action::Choose(_) => unreachable!(),
+        });
+
+    Search { best, nodes }
+}
+
+/// Returns the best board to send the opponent to, for the player who must
+/// choose it with `SendToClosed::OpponentChooses`, searching `depth` moves
+/// ahead like [`best_move`]. Choosing a board is not a move.
+///
+/// Returns `None` if no choice is pending (see `STTT::pending_choice`).
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, ai};
+///
+/// // Makes the next decision, whether a move or a board choice
+/// fn step(game: &mut STTT) {
+///     match game.pending_choice() {
+///         Some(chooser) => game.choose_board(chooser, ai::best_board(game, 2).unwrap()).unwrap(),
+///         None => { game.play_move(ai::best_move(game, 2).unwrap()).unwrap(); },
+///     }
+/// }
+///
+/// let mut game = STTT::new();
+/// step(&mut game);
+/// assert_eq!(ai::best_board(&game, 2), None);
+/// ```
+pub fn best_board(game: &STTT, depth: usize) -> Option<usize> {
+    let chooser = game.pending_choice()?;
+
+    match root(game, chooser, actions(game).1, depth, true, &mut 1)? {
+        Action::Choose(board_idx) => Some(board_idx),
+        Action::Play(_) => unreachable!(),
+    }
+}
+
+/// Returns the fewest moves the next player needs to win the game against
+/// any defence, trying up to `max_depth` of their moves, or `None` if they
+/// can't force a win that soon.
+///
+/// Each depth is searched with [`best_move`]'s minimax, deepening one move
+/// at a time, so that the first forced win found is the shortest.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, ai};
+///
+/// // X owns boards 0 and 1, and can take board 2 at 20
+/// let game = STTT::from_code(&format!("XXX......XXX......XX{}X", ".".repeat(61))).unwrap();
+/// assert_eq!(ai::shortest_mate(&game, 2), Some(1));
+/// assert_eq!(ai::shortest_mate(&STTT::new(), 1), None);
+/// ```
+pub fn shortest_mate(game: &STTT, max_depth: usize) -> Option<usize> {
+    if game.is_over() {
+        return None;
+    }
+
+    (1..=max_depth).find(|&n| forces_win(game, game.player(), 2 * n - 1))
+}
+
+/// Returns every legal move that keeps a forced win for the next player
+/// within `depth` of their moves, counting the move itself, ordered by
+/// their absolute index.
+///
+/// The result is empty if [`shortest_mate`] finds no forced win that soon.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, Position, ai};
+///
+/// // X owns boards 0 and 1, and can take board 2 at 20
+/// let game = STTT::from_code(&format!("XXX......XXX......XX{}X", ".".repeat(61))).unwrap();
+/// assert_eq!(ai::all_mating_moves(&game, 1), vec![Position::from_absolute(20).unwrap()]);
+/// assert_eq!(ai::all_mating_moves(&STTT::new(), 1), vec![]);
+/// ```
+pub fn all_mating_moves(game: &STTT, depth: usize) -> Vec<Position> {
+    if shortest_mate(game, depth).is_none() {
+        return Vec::new();
+    }
+
+    let me = game.player();
+    game.valid_moves().into_iter()
+        .filter(|&position| {
+            let (child, _) = after(game, Action::Play(position), 1);
+            forces_win(&child, me, 2 * depth - 2)
+        })
+        .collect()
+}
+
+/// Returns `true` if `me` wins the game within `depth` moves, whatever the
+/// opponent plays
+fn forces_win(game: &STTT, me: Player, depth: usize) -> bool {
+    alphabeta(game, depth, me, -i32::MAX, i32::MAX, true, &mut 0) >= WIN
+}
+
+/// Returns `true` if `me` loses the game within `depth` moves, whatever
+/// they play
+pub(crate) fn forces_loss(game: &STTT, me: Player, depth: usize) -> bool {
+    alphabeta(game, depth, me, -i32::MAX, i32::MAX, true, &mut 0) <= -WIN
+}
+
+/// A decision in the game tree
+#[derive(Copy, Clone)]
+enum Action {
+    /// The next player plays in the position
+    Play(Position),
+    /// The opponent sends the next player to the board
+    Choose(usize),
+}
+
+/// Returns who decides next and the actions they can take
+fn actions(game: &STTT) -> (Player, Vec<Action>) {
+    match game.pending_choice() {
+        Some(chooser) => {
+            let boards = (0..9).filter(|&board_idx| game.is_open(board_idx));
+            (chooser, boards.map(Action::Choose).collect())
+        },
+        None => (game.player(), game.valid_moves().into_iter().map(Action::Play).collect()),
+    }
+}
+
+/// Returns the game after the action, and the depth left to search after
+/// it, since choosing a board is not a move
+fn after(game: &STTT, action: Action, depth: usize) -> (STTT, usize) {
+    let mut child = game.clone();
+    match action {
+        Action::Play(position) => {
+            child.play_move(position).unwrap();
+            (child, depth - 1)
+        },
+        Action::Choose(board_idx) => {
+            child.choose_board(game.player().opponent(), board_idx).unwrap();
+            (child, depth)
+        },
+    }
+}
+
+/// Returns the best of the actions `me` can take, the first one among
+/// equally good actions
+fn root(game: &STTT, me: Player, actions: Vec<Action>, depth: usize, pruning: bool, nodes: &mut usize) -> Option<Action> {
+    let mut best: Option<(Action, i32)> = None;
+
+    for action in actions {
+        let (child, depth) = after(game, action, depth);
+
+        // Actions that can't beat the best so far may be cut short
+        let alpha = match best {
+            Some((_, best_score)) if pruning => best_score,
+            _ => -i32::MAX,
+        };
+        let score = alphabeta(&child, depth, me, alpha, i32::MAX, pruning, nodes);
+
+        match best {
+            Some((_, best_score)) if best_score >= score => {},
+            _ => best = Some((action, score)),
+        }
+    }
+
+    best.map(|(action, _)| action)
+}
+
+/// Scores the game for `me`, searching `depth` moves ahead. Scores outside
+/// `alpha..beta` are only bounds, unless `pruning` is off.
+fn alphabeta(game: &STTT, depth: usize, me: Player, mut alpha: i32, mut beta: i32, pruning: bool, nodes: &mut usize) -> i32 {
+    *nodes += 1;
+
+    match game.status() {
+        Status::Winner(p) if p == me => return WIN + depth as i32,
+        Status::Winner(_) => return -WIN - depth as i32,
+        Status::Tie => return 0,
+        Status::InProgress => {},
+    }
+
+    if depth == 0 {
+        return material(game, me);
+    }
+
+    let (decider, actions) = actions(game);
+    let maximizing = decider == me;
+    let mut value = if maximizing { -i32::MAX } else { i32::MAX };
+
+    for action in actions {
+        let (child, depth) = after(game, action, depth);
+        let score = alphabeta(&child, depth, me, alpha, beta, pruning, nodes);
+
+        if maximizing {
+            value = value.max(score);
+            alpha = alpha.max(value);
+        } else {
+            value = value.min(score);
+            beta = beta.min(value);
+        }
+
+        if pruning && alpha >= beta {
+            break;
+        }
+    }
+
+    value
+}
+
+/// Counts the positions reached after playing every sequence of `depth`
+/// legal moves, to check move generation. Positions where the game ends
+/// sooner count as one.
+///
+/// With `SendToClosed::OpponentChooses`, each board the opponent may choose
+/// leads to different positions, but choosing is not a move.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, ai};
+///
+/// assert_eq!(ai::perft(&STTT::new(), 0), 1);
+/// assert_eq!(ai::perft(&STTT::new(), 1), 81);
+/// // O is sent to a board with 9 empty tiles, or 8 if X played in the
+/// // board with the same index as the tile: 72 * 9 + 9 * 8
+/// assert_eq!(ai::perft(&STTT::new(), 2), 720);
+/// ```
+pub fn perft(game: &STTT, depth: usize) -> u64 {
+    let actions = actions(game).1;
+    if depth == 0 || actions.is_empty() {
+        return 1;
+    }
+
+    actions.into_iter()
+        .map(|action| {
+            let (child, depth) = after(game, action, depth);
+            perft(&child, depth)
+        })
+        .sum()
+}
+
+/// Number of boards won by `me` minus the ones won by the opponent
+fn material(game: &STTT, me: Player) -> i32 {
+    game.board_ref().metaboard().iter()
+        .map(|&owner| match owner {
+            Some(p) if p == me => 1,
+            Some(_) => -1,
+            None => 0,
+        })
+        .sum()
+}
+
+/// A player choosing uniformly random legal moves, reproducibly from a seed.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, ai::RandomBot};
+///
+/// let mut game = STTT::new();
+/// let mut a = RandomBot::new(42);
+/// let mut b = RandomBot::new(42);
+///
+/// while let Some(position) = a.choose(&game) {
+///     assert_eq!(b.choose(&game), Some(position));
+///     game.play_move(position).unwrap();
+/// }
+/// assert_eq!(b.choose(&game), None);
+/// ```
+///
+/// With `SendToClosed::OpponentChooses`, the bot also chooses the boards:
+///
+/// ```
+/// use sttt::{STTT, RuleSet, SendToClosed, ai::RandomBot};
+///
+/// let rules = RuleSet { send_to_closed: SendToClosed::OpponentChooses, ..RuleSet::default() };
+///
+/// for seed in 1..20 {
+///     let mut game = STTT::with_rules(rules);
+///     let mut bot = RandomBot::new(seed);
+///
+///     while !game.is_over() {
+///         match game.pending_choice() {
+///             Some(chooser) => {
+///                 assert_eq!(bot.choose(&game), None);
+///                 let board_idx = bot.choose_board(&game).unwrap();
+///                 game.choose_board(chooser, board_idx).unwrap();
+///             },
+///             None => {
+///                 assert_eq!(bot.choose_board(&game), None);
+///                 game.play_move(bot.choose(&game).unwrap()).unwrap();
+///             },
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RandomBot {
+    state: u64,
+}
+
+impl RandomBot {
+    /// Creates a bot whose choices are determined by `seed`
+    pub fn new(seed: u64) -> RandomBot {
+        // xorshift gets stuck on zero
+        let state = if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed };
+        RandomBot { state }
+    }
+
+    /// Returns a random legal move for the next player, or `None` if there
+    /// are no legal moves, e.g. while a board choice is pending.
+    pub fn choose(&mut self, game: &STTT) -> Option<Position> {
+        let moves = game.valid_moves();
+        if moves.is_empty() {
+            return None;
+        }
+
+        // The modulo bias is negligible with at most 81 moves
+        let idx = (self.next() % moves.len() as u64) as usize;
+        Some(moves[idx])
+    }
+
+    /// Returns a random open board for the player who must choose where the
+    /// opponent plays, or `None` if no choice is pending (see
+    /// `STTT::pending_choice`).
+    pub fn choose_board(&mut self, game: &STTT) -> Option<usize> {
+        game.pending_choice()?;
+
+        let boards: Vec<usize> = (0..9).filter(|&board_idx| game.is_open(board_idx)).collect();
+        let idx = (self.next() % boards.len() as u64) as usize;
+        Some(boards[idx])
+    }
+
+    /// Advances the xorshift64 generator
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
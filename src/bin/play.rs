@@ -8,11 +8,13 @@ use sttt::{STTT, Position};
 
 fn main() {
     println!("Welcome to Super Tic Tac Toe!");
+    println!("Play a square by its number (40) or by board and tile (4 4 or 4:4).");
+    println!("Type h for the number of each square.");
 
     let mut game = STTT::new();
 
     loop {
-        println!("{}", game.board());
+        println!("{}", game);
 
         // loop until valid play
         loop {
@@ -24,15 +26,12 @@ fn main() {
                 .read_line(&mut square)
                 .expect("Failed to read from stdin");
 
-            let square: usize = match square.trim().parse() {
-                Ok(val) => val,
-                Err(_) => {
-                    println!("Please type a number!");
-                    continue;
-                }
-            };
+            if square.trim() == "h" || square.trim() == "help" {
+                println!("{}", STTT::render_help());
+                continue;
+            }
 
-            let pos = match Position::from_absolute(square) {
+            let pos = match Position::parse(&square) {
                 Ok(val) => val,
                 Err(msg) => {
                     println!("{}", msg);
@@ -40,19 +39,29 @@ fn main() {
                 }
             };
 
-            match game.play(game.player(), pos) {
-                Ok(status) => match status {
-                    sttt::Status::Winner(p) => {
-                        println!("{}", game.board());
-                        println!("{} wins!", p);
-                        return;
-                    },
-                    sttt::Status::Tie => {
-                        println!("{}", game.board());
-                        println!("Game ended in a tie");
-                        return;
-                    },
-                    _ => break,
+            let metaboard = game.board().metaboard();
+
+            match game.play_move(pos) {
+                Ok(status) => {
+                    if metaboard[pos.board_idx()].is_none() {
+                        if let Some(p) = game.board().metaboard()[pos.board_idx()] {
+                            println!("{} wins board {}!!", p, pos.board_idx());
+                        }
+                    }
+
+                    match status {
+                        sttt::Status::Winner(p) => {
+                            println!("{}", game.board());
+                            println!("{} wins!", p);
+                            return;
+                        },
+                        sttt::Status::Tie => {
+                            println!("{}", game.board());
+                            println!("Game ended in a tie");
+                            return;
+                        },
+                        _ => break,
+                    }
                 },
                 Err(s) => println!("Error: {}", s),
             }
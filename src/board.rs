@@ -1,8 +1,48 @@
 use std::fmt;
+use std::str::FromStr;
 
 use super::Player;
 
-#[derive(Copy, Clone)]
+/// The eight lines of three tiles that win a 3x3 board
+pub(crate) const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2], [3, 4, 5], [6, 7, 8],
+    [0, 3, 6], [1, 4, 7], [2, 5, 8],
+    [0, 4, 8], [2, 4, 6],
+];
+
+/// `LINES` as bitmasks, where bit `i` stands for tile `i`
+const LINE_MASKS: [u16; 8] = [
+    0b000_000_111, 0b000_111_000, 0b111_000_000,
+    0b001_001_001, 0b010_010_010, 0b100_100_100,
+    0b100_010_001, 0b001_010_100,
+];
+
+/// Bitmask with every tile of a 3x3 board set
+const FULL_MASK: u16 = 0b111_111_111;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// Represents the state of a small board, as seen from the metaboard.
+pub enum SubBoardResult {
+    /// The board can still be played in.
+    Open,
+    /// The board was won by `Player`.
+    Won(Player),
+    /// Every tile of the board was played and nobody won it.
+    Drawn,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// Represents why a game is, or is bound to end, in a tie.
+pub enum DrawReason {
+    /// Every board was won or filled and no player has a line in the metaboard.
+    AllBoardsDecidedNoLine,
+    /// Every line in the metaboard has boards won by both players or a filled board.
+    AllMetaboardLinesBlocked,
+    /// No board that is still open can be won by either player.
+    AllRemainingBoardsDead,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 /// Represents a valid position in the board
 pub struct Position {
     board_idx: usize,
@@ -61,8 +101,51 @@ impl Position {
         })
     }
 
+    /// Creates a valid position from the index of a small board in the metaboard
+    /// and the index of a tile in that small board.
+    ///
+    /// Both indices follow the same numeration as the metaboard:
+    /// ```text
+    ///    0 | 1 | 2
+    ///   ---+---+---
+    ///    3 | 4 | 5
+    ///   ---+---+---
+    ///    6 | 7 | 8
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// If any of the indices is greater than 8, this method returns an error
+    ///
+    /// # Examples
+    /// ```
+    /// use sttt::Position;
+    ///
+    /// for board_idx in 0..9 {
+    ///     for tile_idx in 0..9 {
+    ///         assert_eq!(Position::from_relative(board_idx, tile_idx),
+    ///                    Position::from_absolute(board_idx * 9 + tile_idx));
+    ///     }
+    /// }
+    /// assert!(Position::from_relative(9, 0).is_err());
+    /// assert!(Position::from_relative(0, 9).is_err());
+    /// ```
+    pub fn from_relative(board_idx: usize, tile_idx: usize) -> Result<Position, &'static str> {
+        if board_idx >= 9 {
+            return Err("Board index outside of metaboard");
+        }
+        if tile_idx >= 9 {
+            return Err("Tile index outside of board");
+        }
+
+        Ok(Position {
+            board_idx,
+            tile_idx,
+        })
+    }
+
     /// Returns the index of the small board in the metaboard that corresponds
-    /// to this position
+    /// to this position, in `0..9`
     ///
     /// # Examples
     ///
@@ -71,10 +154,14 @@ impl Position {
     ///
     /// let pos = Position::from_absolute(42).unwrap();
     /// assert_eq!(pos.board_idx(), 4);
+    ///
+    /// let center = Position::from_absolute(40).unwrap();
+    /// assert_eq!(center.board_idx(), 4);
     /// ```
     pub fn board_idx(&self) -> usize { self.board_idx }
 
-    /// Returns the index of the tile in the small board corresponding to the position
+    /// Returns the index of the tile in the small board corresponding to the position,
+    /// in `0..9`
     ///
     /// # Examples
     ///
@@ -83,17 +170,220 @@ impl Position {
     ///
     /// let pos = Position::from_absolute(42).unwrap();
     /// assert_eq!(pos.tile_idx(), 6);
+    ///
+    /// let center = Position::from_absolute(40).unwrap();
+    /// assert_eq!(center.tile_idx(), 4);
     /// ```
     pub fn tile_idx(&self) -> usize { self.tile_idx }
+
+    /// Returns the absolute index of this position in the board, in `0..81`.
+    ///
+    /// This is the inverse of `Position::from_absolute`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Position;
+    ///
+    /// for i in 0..81 {
+    ///     assert_eq!(Position::from_absolute(i).unwrap().to_absolute(), i);
+    /// }
+    /// ```
+    pub fn to_absolute(&self) -> usize { self.board_idx * 9 + self.tile_idx }
+
+    /// Returns the row of this position in the 9x9 grid of tiles, in `0..9`.
+    ///
+    /// Rows are counted from the top of the board, across the small boards:
+    /// rows `0..3` go through small boards 0, 1 and 2, rows `3..6` through
+    /// small boards 3, 4 and 5, and rows `6..9` through small boards 6, 7 and 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Position;
+    ///
+    /// let row = |i| Position::from_absolute(i).unwrap().global_row();
+    /// assert_eq!(row(0), 0);  // top left
+    /// assert_eq!(row(20), 0); // top right
+    /// assert_eq!(row(60), 8); // bottom left
+    /// assert_eq!(row(80), 8); // bottom right
+    /// assert_eq!(row(40), 4); // center
+    /// ```
+    pub fn global_row(&self) -> usize { (self.board_idx / 3) * 3 + self.tile_idx / 3 }
+
+    /// Returns the column of this position in the 9x9 grid of tiles, in `0..9`.
+    ///
+    /// Columns are counted from the left of the board, across the small boards:
+    /// columns `0..3` go through small boards 0, 3 and 6, columns `3..6` through
+    /// small boards 1, 4 and 7, and columns `6..9` through small boards 2, 5 and 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Position;
+    ///
+    /// let col = |i| Position::from_absolute(i).unwrap().global_col();
+    /// assert_eq!(col(0), 0);  // top left
+    /// assert_eq!(col(20), 8); // top right
+    /// assert_eq!(col(60), 0); // bottom left
+    /// assert_eq!(col(80), 8); // bottom right
+    /// assert_eq!(col(40), 4); // center
+    /// ```
+    pub fn global_col(&self) -> usize { (self.board_idx % 3) * 3 + self.tile_idx % 3 }
+
+    /// Parses a position typed by a player, either as an absolute position
+    /// (`40`), or as a board and a tile index separated by whitespace (`4 4`)
+    /// or by a colon (`4:4`). Surrounding whitespace is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message describing the problem if the input is not in one
+    /// of those forms or the position is outside of the board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Position;
+    ///
+    /// let center = Position::from_absolute(40).unwrap();
+    /// assert_eq!(Position::parse("40"), Ok(center));
+    /// assert_eq!(Position::parse("4 4"), Ok(center));
+    /// assert_eq!(Position::parse("4:4"), Ok(center));
+    /// assert_eq!(Position::parse("  4  4\n"), Ok(center));
+    /// assert_eq!(Position::parse(" 4 : 4 "), Ok(center));
+    /// assert_eq!(Position::parse("2 0"), Ok(Position::from_absolute(18).unwrap()));
+    ///
+    /// assert!(Position::parse("").is_err());
+    /// assert!(Position::parse("81").is_err());
+    /// assert!(Position::parse("4 9").is_err());
+    /// assert!(Position::parse("4:").is_err());
+    /// assert!(Position::parse("1 2 3").is_err());
+    /// assert!(Position::parse("a b").is_err());
+    /// ```
+    pub fn parse(input: &str) -> Result<Position, String> {
+        let input = input.trim();
+        let colon = input.contains(':');
+        let parts: Vec<&str> = input
+            .split(|c: char| c == ':' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        let index = |part: &str| part.parse::<usize>()
+            .map_err(|_| format!("'{}' is not a number", part));
+
+        match (parts.as_slice(), colon) {
+            ([absolute], false) => Position::from_absolute(index(absolute)?).map_err(String::from),
+            ([board_idx, tile_idx], _) => {
+                Position::from_relative(index(board_idx)?, index(tile_idx)?).map_err(String::from)
+            },
+            _ => Err(format!("Expected a position like 40, 4 4 or 4:4, got '{}'", input)),
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    /// Formats the position as `<board_idx>:<tile_idx>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Position;
+    ///
+    /// let pos = Position::from_absolute(40).unwrap();
+    /// assert_eq!(pos.to_string(), "4:4");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.board_idx, self.tile_idx)
+    }
+}
+
+impl FromStr for Position {
+    type Err = &'static str;
+
+    /// Parses a position in the `<board_idx>:<tile_idx>` format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Position;
+    ///
+    /// for i in 0..81 {
+    ///     let pos = Position::from_absolute(i).unwrap();
+    ///     assert_eq!(pos.to_string().parse(), Ok(pos));
+    /// }
+    ///
+    /// assert!("4".parse::<Position>().is_err());
+    /// assert!("4:9".parse::<Position>().is_err());
+    /// assert!("a:1".parse::<Position>().is_err());
+    /// assert!("1:2:3".parse::<Position>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Position, Self::Err> {
+        let mut parts = s.split(':');
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(board_idx), Some(tile_idx), None) => {
+                let board_idx = board_idx.parse().map_err(|_| "Invalid board index")?;
+                let tile_idx = tile_idx.parse().map_err(|_| "Invalid tile index")?;
+                Position::from_relative(board_idx, tile_idx)
+            },
+            _ => Err("Position must be in the <board>:<tile> format"),
+        }
+    }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Eq, Hash, PartialEq)]
 /// Represents the Super Tic-Tac-Toe board.
 /// It has 9 Tic-Tac-Toe boards (also called small boards) in a
 /// 3x3 grid (also called metaboard).
+///
+/// Two boards are equal if they have the same tiles played by the same
+/// players, and so the same metaboard. Equal boards hash equally, so boards
+/// can be used as keys of a `HashMap`, e.g. to cache evaluated positions.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{Board, STTT, Player, Position};
+///
+/// let moves: Vec<Position> = [40, 36, 0].iter()
+///     .map(|&i| Position::from_absolute(i).unwrap())
+///     .collect();
+///
+/// let mut board = Board::new();
+/// for (&pos, &player) in moves.iter().zip([Player::X, Player::O, Player::X].iter()) {
+///     board.play(player, pos).unwrap();
+/// }
+/// let game = STTT::from_moves(&moves).unwrap();
+/// assert!(board == game.board());
+///
+/// board.play(Player::O, Position::from_absolute(4).unwrap()).unwrap();
+/// assert!(board != game.board());
+/// ```
+///
+/// ```
+/// use std::collections::HashMap;
+/// use sttt::{Board, STTT, Player, Position};
+///
+/// let mut cache = HashMap::new();
+/// let mut game = STTT::new();
+/// for &i in &[40, 36, 0] {
+///     game.play_move(Position::from_absolute(i).unwrap()).unwrap();
+///     cache.insert(game.board(), game.history().len());
+/// }
+///
+/// let mut board = Board::new();
+/// board.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+/// board.play(Player::O, Position::from_absolute(36).unwrap()).unwrap();
+/// assert_eq!(cache.get(&board), Some(&2));
+/// assert_eq!(cache.get(&Board::new()), None);
+/// ```
 pub struct Board {
-    board: [[Option<Player>;9];9],
-    metaboard: [Option<Player>;9],
+    // Bit `t` of `x[b]` is set if X played tile `t` of board `b`
+    x: [u16; 9],
+    o: [u16; 9],
+    // Bit `b` is set if the player won board `b`
+    meta_x: u16,
+    meta_o: u16,
 }
 
 impl Board {
@@ -109,11 +399,173 @@ impl Board {
     pub fn new() -> Board {
 
         Board {
-            board: [[None; 9]; 9],
-            metaboard: [None; 9],
+            x: [0; 9],
+            o: [0; 9],
+            meta_x: 0,
+            meta_o: 0,
+        }
+    }
+
+    /// Clears every tile and the metaboard, leaving the board in the same
+    /// state as `Board::new()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_absolute(0).unwrap());
+    /// board.play(Player::X, Position::from_absolute(1).unwrap());
+    /// board.play(Player::X, Position::from_absolute(2).unwrap());
+    ///
+    /// board.reset();
+    /// assert_eq!(board.to_string(), Board::new().to_string());
+    /// ```
+    pub fn reset(&mut self) {
+        *self = Board::new();
+    }
+
+    /// Creates a `Board` with the given marks already placed.
+    ///
+    /// The metaboard is recomputed from the resulting small boards.
+    /// Turn alternation and reachability are not checked, so this is
+    /// meant for setting up test and analysis scenarios only.
+    ///
+    /// The order of the moves is unknown, so a board where both players have
+    /// a line, as `RuleSet::play_in_won_boards` allows, goes to whoever has
+    /// the first line among the rows, then the columns, then the diagonals.
+    /// `STTT::decode` and `STTT::from_code` restore the actual owner.
+    ///
+    /// # Errors
+    ///
+    /// If the same position appears more than once, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// // X wins two boards without O ever playing
+    /// let cells: Vec<(Position, Player)> = [0, 1, 2, 72, 76, 80].iter()
+    ///     .map(|&i| (Position::from_absolute(i).unwrap(), Player::X))
+    ///     .collect();
+    ///
+    /// let board = Board::from_cells(&cells).unwrap();
+    /// assert_eq!(board.metaboard(),  [Some(Player::X), None, None,
+    ///                                     None, None, None,
+    ///                                     None, None, Some(Player::X)]);
+    ///
+    /// let p = Position::from_absolute(0).unwrap();
+    /// assert!(Board::from_cells(&[(p, Player::X), (p, Player::O)]).is_err());
+    /// ```
+    pub fn from_cells(cells: &[(Position, Player)]) -> Result<Board, &'static str> {
+        let mut res = Board::new();
+
+        for &(position, player) in cells {
+            let (board_idx, tile_idx) = (position.board_idx(), position.tile_idx());
+            if res.get(board_idx, tile_idx).is_some() {
+                return Err("That square is not empty");
+            }
+            res.tiles_mut(player)[board_idx] |= 1 << tile_idx;
+        }
+
+        for board_idx in 0..9 {
+            res.update_metaboard(board_idx);
+        }
+
+        Ok(res)
+    }
+
+    /// Creates a `Board` from the tiles of each small board, indexed as in
+    /// `Board::get`.
+    ///
+    /// Like `Board::from_cells`, the metaboard is recomputed from the small
+    /// boards and nothing about the legality of the position is checked.
+    /// Boards where both players have a line are given away the same way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player};
+    ///
+    /// let mut cells = [[None; 9]; 9];
+    /// // O wins board 5 on its diagonal
+    /// cells[5][0] = Some(Player::O);
+    /// cells[5][4] = Some(Player::O);
+    /// cells[5][8] = Some(Player::O);
+    /// cells[7][4] = Some(Player::X);
+    ///
+    /// let board = Board::from_grid(cells);
+    /// assert_eq!(board.metaboard(), [None, None, None,
+    ///                                None, None, Some(Player::O),
+    ///                                None, None, None]);
+    /// assert_eq!(board.get(7, 4), Some(Player::X));
+    /// ```
+    pub fn from_grid(cells: [[Option<Player>; 9]; 9]) -> Board {
+        let mut res = Board::new();
+
+        for (board_idx, small_board) in cells.iter().enumerate() {
+            for (tile_idx, &tile) in small_board.iter().enumerate() {
+                if let Some(player) = tile {
+                    res.tiles_mut(player)[board_idx] |= 1 << tile_idx;
+                }
+            }
+            res.update_metaboard(board_idx);
+        }
+
+        res
+    }
+
+    /// Returns the player occupying the given tile, or `None` if it is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `board_idx` or `tile_idx` are greater or equal than `9`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_absolute(40).unwrap());
+    /// board.play(Player::O, Position::from_absolute(36).unwrap());
+    ///
+    /// assert_eq!(board.get(4, 4), Some(Player::X));
+    /// assert_eq!(board.get(4, 0), Some(Player::O));
+    /// assert_eq!(board.get(0, 4), None);
+    /// ```
+    pub fn get(&self, board_idx: usize, tile_idx: usize) -> Option<Player> {
+        assert!(board_idx < 9 && tile_idx < 9);
+
+        let bit = 1 << tile_idx;
+        if self.x[board_idx] & bit != 0 {
+            Some(Player::X)
+        } else if self.o[board_idx] & bit != 0 {
+            Some(Player::O)
+        } else {
+            None
         }
     }
 
+    /// Returns the player occupying the tile at the given position, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let pos = Position::from_absolute(40).unwrap();
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, pos).unwrap();
+    /// assert_eq!(game.board().get_by_position(pos), Some(Player::X));
+    /// ```
+    pub fn get_by_position(&self, pos: Position) -> Option<Player> {
+        self.get(pos.board_idx(), pos.tile_idx())
+    }
+
     /// Returns a copy of the metaboard
     ///
     /// # Examples
@@ -125,7 +577,64 @@ impl Board {
     /// let metaboard = board.metaboard();
     /// ```
     pub fn metaboard(&self) -> [Option<Player>; 9] {
-        return self.metaboard;
+        let mut res = [None; 9];
+        for (board_idx, owner) in res.iter_mut().enumerate() {
+            *owner = self.owner(board_idx);
+        }
+        res
+    }
+
+    /// Returns an iterator over the metaboard, yielding the index of each
+    /// board together with the player who won it, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let cells: Vec<(Position, Player)> = [36, 40, 44].iter()
+    ///     .map(|&i| (Position::from_absolute(i).unwrap(), Player::O))
+    ///     .collect();
+    /// let board = Board::from_cells(&cells).unwrap();
+    ///
+    /// let cells: Vec<(usize, Option<Player>)> = board.metaboard_iter().collect();
+    /// let expected: Vec<(usize, Option<Player>)> = board.metaboard().iter()
+    ///     .cloned()
+    ///     .enumerate()
+    ///     .collect();
+    /// assert_eq!(cells.len(), 9);
+    /// assert_eq!(cells, expected);
+    /// assert_eq!(cells[4], (4, Some(Player::O)));
+    /// ```
+    pub fn metaboard_iter(&self) -> impl Iterator<Item = (usize, Option<Player>)> + '_ {
+        (0..9).map(move |board_idx| (board_idx, self.owner(board_idx)))
+    }
+
+    /// Returns an iterator over the 81 tiles, yielding the position of each
+    /// tile together with the player occupying it, in absolute position
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    /// board.play(Player::O, Position::from_absolute(80).unwrap()).unwrap();
+    ///
+    /// let cells: Vec<(Position, Option<Player>)> = board.cells().collect();
+    /// assert_eq!(cells.len(), 81);
+    /// assert_eq!(cells[0], (Position::from_absolute(0).unwrap(), None));
+    /// assert_eq!(cells[40], (Position::from_absolute(40).unwrap(), Some(Player::X)));
+    /// assert_eq!(cells[80], (Position::from_absolute(80).unwrap(), Some(Player::O)));
+    /// assert_eq!(board.cells().filter(|(_, tile)| tile.is_some()).count(), 2);
+    /// ```
+    pub fn cells(&self) -> impl Iterator<Item = (Position, Option<Player>)> + '_ {
+        (0..81).map(move |pos| {
+            let position = Position::from_absolute(pos).unwrap();
+            (position, self.get_by_position(position))
+        })
     }
 
     /// Inserts a move from a given player in the board.
@@ -133,13 +642,13 @@ impl Board {
     /// If that player wins the small board, the metaboard will 
     /// have that player in the position corresponding to the closed board
     ///
-    /// # Errors
+    /// This is the same move `STTT::play` makes, without checking turns or
+    /// which boards are valid to play in.
     ///
-    /// If board_idx or tile_idx are greater or equal than `9`, that move is invalid
-    /// and an error is returned.
+    /// # Errors
     ///
-    /// If the given position corresponds to an already played tile, 
-    /// an error is returned as well.
+    /// If the given position corresponds to an already played tile,
+    /// an error is returned.
     ///
     /// # Examples
     /// 
@@ -158,6 +667,37 @@ impl Board {
     ///                                     None, None, None, 
     ///                                     None, None, None]);
     /// ```
+    ///
+    /// Playing directly in a `Board` and through `STTT` has the same effect:
+    ///
+    /// ```
+    /// use sttt::{Board, STTT, Player, Position};
+    ///
+    /// let pos = Position::from_absolute(40).unwrap();
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, pos).unwrap();
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, pos).unwrap();
+    ///
+    /// assert_eq!(board.to_string(), game.board().to_string());
+    /// assert!(board.play(Player::O, pos).is_err());
+    /// ```
+    ///
+    /// A won board keeps its winner, even if the other player completes a
+    /// line in it later:
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// for &(player, pos) in &[(Player::X, 0), (Player::X, 1), (Player::X, 2),
+    ///                         (Player::O, 3), (Player::O, 4), (Player::O, 5)] {
+    ///     board.play(player, Position::from_absolute(pos).unwrap()).unwrap();
+    /// }
+    /// assert_eq!(board.metaboard()[0], Some(Player::X));
+    /// ```
     pub fn play(
         &mut self,
         player: Player,
@@ -167,17 +707,18 @@ impl Board {
         let board_idx = position.board_idx();
         let tile_idx = position.tile_idx();
 
-        if self.board[board_idx][tile_idx].is_some() {
+        if self.get(board_idx, tile_idx).is_some() {
             return Err("That square is not empty");
         }
 
-        self.board[board_idx][tile_idx] = Some(player);
+        self.tiles_mut(player)[board_idx] |= 1 << tile_idx;
 
-        if let Some(board_winner) = Board::check_winner(&self.board[board_idx]) {
-            assert!(board_winner == player);
-            println!("{} wins board {}!!", board_winner, board_idx);
-
-            self.metaboard[board_idx] = Some(player);
+        // The first player to complete a line keeps the board
+        if self.owner(board_idx).is_none() && Board::has_line(self.tiles(player)[board_idx]) {
+            match player {
+                Player::X => self.meta_x |= 1 << board_idx,
+                Player::O => self.meta_o |= 1 << board_idx,
+            }
         }
 
         Ok(())
@@ -204,99 +745,838 @@ impl Board {
     /// assert_eq!(board.is_open(0),  false);
     /// ```
     pub fn is_open(&self, board_idx: usize) -> bool {
-        assert!(board_idx < 9);
-
-        // nobody has won this board
-        self.metaboard[board_idx].is_none() &&
-        // still has empty squares
-        self.board[board_idx].iter()
-            .filter(|x| x.is_none())
-            .count() > 0
+        self.sub_board_result(board_idx) == SubBoardResult::Open
     }
 
-    /// Tic-Tac-Toe logic to check if a 3x3 board has a winner
+    /// Returns whether the given small board is still open, was won, or
+    /// ended in a draw.
+    ///
+    /// A drawn board is closed, but counts for neither player in the metaboard,
+    /// where it shows as `None`.
     ///
     /// # Examples
-    /// 
+    ///
     /// ```
-    /// use sttt::{Board, Player};
+    /// use sttt::{Board, Player, Position, SubBoardResult};
+    /// use sttt::Player::{X, O};
     ///
-    /// let mut ttt: [Option<Player>; 9] = [None; 9];
-    /// ttt[0] = Some(Player::X);
-    /// ttt[1] = Some(Player::X);
-    /// assert_eq!(Board::check_winner(&ttt),  None);
-    /// ttt[2] = Some(Player::X);
-    /// assert_eq!(Board::check_winner(&ttt),  Some(Player::X));
+    /// // X wins boards 0 and 1 on their top rows...
+    /// let mut cells: Vec<(Position, Player)> = [0, 1, 2, 9, 10, 11].iter()
+    ///     .map(|&i| (Position::from_absolute(i).unwrap(), X))
+    ///     .collect();
+    /// // ...and board 2 is filled without a winner
+    /// let drawn = [X, O, X,
+    ///              X, O, O,
+    ///              O, X, X];
+    /// for (tile_idx, &player) in drawn.iter().enumerate() {
+    ///     cells.push((Position::from_absolute(18 + tile_idx).unwrap(), player));
+    /// }
+    /// let board = Board::from_cells(&cells).unwrap();
+    ///
+    /// assert_eq!(board.sub_board_result(0), SubBoardResult::Won(X));
+    /// assert_eq!(board.sub_board_result(2), SubBoardResult::Drawn);
+    /// assert_eq!(board.sub_board_result(3), SubBoardResult::Open);
+    /// assert!(!board.is_open(2));
+    /// assert_eq!(board.metaboard()[2], None);
+    /// assert_eq!(board.winner(), None);
+    ///
+    /// // drawn boards are shown as `-` in the displayed metaboard
+    /// assert!(board.to_string().lines().nth(9).unwrap().ends_with(" X | X | - "));
     /// ```
-    pub fn check_winner(board: &[Option<Player>;9]) -> Option<Player> {
-        // Check rows
-        for row in 0..3 {
-            let row_base = row * 3;
-            if  board[row_base    ] == board[row_base + 1] &&
-                board[row_base + 1] == board[row_base + 2] &&
-                board[row_base    ].is_some() {
-                    return board[row_base];
-            }
+    pub fn sub_board_result(&self, board_idx: usize) -> SubBoardResult {
+        assert!(board_idx < 9);
+
+        match self.owner(board_idx) {
+            Some(player) => SubBoardResult::Won(player),
+            None if self.empty_tiles(board_idx) == 0 => SubBoardResult::Drawn,
+            None => SubBoardResult::Open,
         }
+    }
 
-        // Check cols
-        for col in 0..3 {
-            if  board[col    ] == board[col + 3] &&
-                board[col + 3] == board[col + 6] &&
-                board[col    ].is_some() {
-                    return board[col];
-            }
+    /// Empties the tile at the given position, updating the metaboard.
+    ///
+    /// As in `Board::play`, a won board keeps its winner while they still
+    /// have a line in it, even if the other player has one too.
+    pub(crate) fn clear(&mut self, position: Position) {
+        let board_idx = position.board_idx();
+        let owner = self.owner(board_idx);
+
+        let bit = !(1 << position.tile_idx());
+
+        self.x[board_idx] &= bit;
+        self.o[board_idx] &= bit;
+
+        match owner {
+            Some(player) if Board::has_line(self.tiles(player)[board_idx]) => {},
+            _ => self.update_metaboard(board_idx),
         }
+    }
+
+    /// Returns `true` if both players have a line in the given board, which
+    /// only `RuleSet::play_in_won_boards` allows
+    pub(crate) fn is_contested(&self, board_idx: usize) -> bool {
+        Board::has_line(self.x[board_idx]) && Board::has_line(self.o[board_idx])
+    }
+
+    /// Gives the given board to `player`, who must have a line in it
+    pub(crate) fn set_owner(&mut self, board_idx: usize, player: Player) {
+        let bit = 1 << board_idx;
 
-        // Check diagonals
-        if  board[4].is_some() && (
-                board[0] == board[4] && board[4] == board[8] ||
-                board[2] == board[4] && board[4] == board[6]
-            ) {
-            return board[4];
+        self.meta_x &= !bit;
+        self.meta_o &= !bit;
+        match player {
+            Player::X => self.meta_x |= bit,
+            Player::O => self.meta_o |= bit,
         }
+    }
 
-        None
+    /// Returns the number of empty tiles in the given small board
+    pub(crate) fn empty_tiles(&self, board_idx: usize) -> usize {
+        (FULL_MASK & !(self.x[board_idx] | self.o[board_idx])).count_ones() as usize
     }
-}
 
-impl fmt::Display for Board {
-    /*
-     *                 |               |
-     *     0 | 1 | 2   |   9 | 10| 11  |   18| 19| 20
-     *    ---+---+---  |  ---+---+---  |  ---+---+--- 
-     *     3 | 4 | 5   |   12| 13| 14  |   21| 22| 23
-     *    ---+---+---  |  ---+---+---  |  ---+---+---
-     *     6 | 7 | 8   |   15| 16| 17  |   24| 25| 26
-     *                 |               |
-     *  ---------------+---------------+---------------             Meta Board:
-     *                 |               | 
-     *     27| 28| 29  |   36| 37| 38  |   45| 46| 47                0 | 1 | 2
-     *    ---+---+---  |  ---+---+---  |  ---+---+---               ---+---+---
-     *     30| 31| 32  |   39| 40| 41  |   48| 49| 50                3 | 4 | 5
-     *    ---+---+---  |  ---+---+---  |  ---+---+---               ---+---+---
-     *     33| 34| 35  |   42| 43| 44  |   51| 52| 53                6 | 7 | 8
-     *                 |               |
-     *  ---------------+---------------+---------------
-     *                 |               |
-     *     54| 55| 56  |   63| 64| 65  |   72| 73| 74
-     *    ---+---+---  |  ---+---+---  |  ---+---+---
-     *     57| 58| 59  |   66| 67| 68  |   75| 76| 77
-     *    ---+---+---  |  ---+---+---  |  ---+---+---
-     *     60| 61| 62  |   69| 70| 71  |   78| 79| 80
-     *                 |               |
-     *
-     */
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        const BIG_ROW_EMPTY: &str = "               |               |";
+    /// Returns a copy of the tiles of the given small board, in row-major
+    /// order: tile `0` is the top left one and tile `8` the bottom right one,
+    /// as in `Position::tile_idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `board_idx` is greater or equal than `9`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_relative(2, 0).unwrap()).unwrap();
+    /// board.play(Player::O, Position::from_relative(2, 4).unwrap()).unwrap();
+    /// board.play(Player::X, Position::from_relative(2, 5).unwrap()).unwrap();
+    ///
+    /// assert_eq!(board.sub_board(2), [Some(Player::X), None, None,
+    ///                                 None, Some(Player::O), Some(Player::X),
+    ///                                 None, None, None]);
+    /// assert_eq!(board.sub_board(1), [None; 9]);
+    /// ```
+    pub fn sub_board(&self, board_idx: usize) -> [Option<Player>; 9] {
+        assert!(board_idx < 9);
+
+        let mut res = [None; 9];
+        for (tile_idx, tile) in res.iter_mut().enumerate() {
+            *tile = self.get(board_idx, tile_idx);
+        }
+        res
+    }
+
+    /// Returns the indices of the empty tiles of the given board, in
+    /// increasing order. If the board was already won or filled, no tile is
+    /// returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `board_idx` is greater or equal than `9`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// assert_eq!(board.available_cells(3), (0..9).collect::<Vec<_>>());
+    ///
+    /// board.play(Player::X, Position::from_relative(3, 0).unwrap()).unwrap();
+    /// board.play(Player::O, Position::from_relative(3, 4).unwrap()).unwrap();
+    /// assert_eq!(board.available_cells(3), vec![1, 2, 3, 5, 6, 7, 8]);
+    ///
+    /// // board 0 is won with empty tiles left
+    /// for &tile_idx in &[0, 4, 8] {
+    ///     board.play(Player::O, Position::from_relative(0, tile_idx).unwrap()).unwrap();
+    /// }
+    /// assert_eq!(board.available_cells(0), vec![]);
+    /// ```
+    pub fn available_cells(&self, board_idx: usize) -> Vec<usize> {
+        if !self.is_open(board_idx) {
+            return Vec::new();
+        }
+
+        let played = self.x[board_idx] | self.o[board_idx];
+        (0..9).filter(|&tile_idx| played & (1 << tile_idx) == 0).collect()
+    }
+
+    /// Returns the number of tiles played by the given player, across every
+    /// small board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// for &pos in &[40, 36, 0, 4, 41, 45, 1] {
+    ///     game.play_move(Position::from_absolute(pos).unwrap()).unwrap();
+    ///
+    ///     let board = game.board();
+    ///     let (x, o) = (board.count(Player::X), board.count(Player::O));
+    ///     assert!(x == o || x == o + 1);
+    /// }
+    /// assert_eq!(game.board().count(Player::X), 4);
+    /// assert_eq!(game.board().count(Player::O), 3);
+    /// ```
+    pub fn count(&self, player: Player) -> usize {
+        self.tiles(player).iter()
+            .map(|mask| mask.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the player who won the given board, if any
+    fn owner(&self, board_idx: usize) -> Option<Player> {
+        if self.meta_x & (1 << board_idx) != 0 {
+            Some(Player::X)
+        } else if self.meta_o & (1 << board_idx) != 0 {
+            Some(Player::O)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the tile masks of the given player
+    fn tiles(&self, player: Player) -> &[u16; 9] {
+        match player {
+            Player::X => &self.x,
+            Player::O => &self.o,
+        }
+    }
+
+    /// Returns the tile masks of the given player, for modification
+    fn tiles_mut(&mut self, player: Player) -> &mut [u16; 9] {
+        match player {
+            Player::X => &mut self.x,
+            Player::O => &mut self.o,
+        }
+    }
+
+    /// Recomputes who won the given board from its tiles
+    fn update_metaboard(&mut self, board_idx: usize) {
+        let bit = 1 << board_idx;
+
+        self.meta_x &= !bit;
+        self.meta_o &= !bit;
+        match Board::mask_winner(self.x[board_idx], self.o[board_idx]) {
+            Some(Player::X) => self.meta_x |= bit,
+            Some(Player::O) => self.meta_o |= bit,
+            None => {},
+        }
+    }
+
+    /// Returns the player occupying the center tile of the given board, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// assert_eq!(board.center_taken(3), None);
+    /// board.play(Player::O, Position::from_absolute(31).unwrap());
+    /// assert_eq!(board.center_taken(3), Some(Player::O));
+    /// assert_eq!(board.center_taken(4), None);
+    /// ```
+    pub fn center_taken(&self, board_idx: usize) -> Option<Player> {
+        assert!(board_idx < 9);
+
+        self.get(board_idx, 4)
+    }
+
+    /// Returns the indices of the open boards where `player` has two tiles
+    /// of a line and the third one is still empty, i.e. boards the player
+    /// can capture in a single move.
+    ///
+    /// This does not take into account which boards are currently valid to
+    /// play in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// // two in a row in board 1...
+    /// board.play(Player::X, Position::from_absolute(9).unwrap());
+    /// board.play(Player::X, Position::from_absolute(10).unwrap());
+    /// // ...and a diagonal threat in board 6
+    /// board.play(Player::X, Position::from_absolute(54).unwrap());
+    /// board.play(Player::X, Position::from_absolute(58).unwrap());
+    /// // blocked line in board 2
+    /// board.play(Player::X, Position::from_absolute(18).unwrap());
+    /// board.play(Player::X, Position::from_absolute(19).unwrap());
+    /// board.play(Player::O, Position::from_absolute(20).unwrap());
+    ///
+    /// assert_eq!(board.near_wins(Player::X), vec![1, 6]);
+    /// assert_eq!(board.near_wins(Player::O), vec![]);
+    /// ```
+    pub fn near_wins(&self, player: Player) -> Vec<usize> {
+        (0..9)
+            .filter(|&board_idx| self.is_open(board_idx))
+            .filter(|&board_idx| {
+                let small_board = self.sub_board(board_idx);
+                LINES.iter().any(|line| {
+                    let owned = line.iter().filter(|&&i| small_board[i] == Some(player)).count();
+                    let empty = line.iter().filter(|&&i| small_board[i].is_none()).count();
+                    owned == 2 && empty == 1
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the lines of the given board that can still be completed,
+    /// together with the player that already has tiles in that line, if any.
+    ///
+    /// A line can still be completed if it doesn't have tiles from both players.
+    /// If the board is no longer open, no line is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_absolute(0).unwrap());
+    /// board.play(Player::O, Position::from_absolute(4).unwrap());
+    /// board.play(Player::X, Position::from_absolute(5).unwrap());
+    ///
+    /// assert_eq!(board.live_lines(0), vec![
+    ///     ([0, 1, 2], Some(Player::X)),
+    ///     ([6, 7, 8], None),
+    ///     ([0, 3, 6], Some(Player::X)),
+    ///     ([1, 4, 7], Some(Player::O)),
+    ///     ([2, 5, 8], Some(Player::X)),
+    ///     ([2, 4, 6], Some(Player::O)),
+    /// ]);
+    /// assert_eq!(board.live_lines(1).len(), 8);
+    /// ```
+    pub fn live_lines(&self, board_idx: usize) -> Vec<([usize; 3], Option<Player>)> {
+        if !self.is_open(board_idx) {
+            return Vec::new();
+        }
+
+        let small_board = self.sub_board(board_idx);
+        LINES.iter()
+            .filter_map(|&line| {
+                let has = |player| line.iter().any(|&i| small_board[i] == Some(player));
+                match (has(Player::X), has(Player::O)) {
+                    (true, true) => None,
+                    (true, false) => Some((line, Some(Player::X))),
+                    (false, true) => Some((line, Some(Player::O))),
+                    (false, false) => Some((line, None)),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the fraction of the 81 tiles that have already been played,
+    /// from `0.0` for an empty board to `1.0` for a full one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// assert_eq!(Board::new().fill_ratio(), 0.0);
+    ///
+    /// let cells: Vec<(Position, Player)> = (0..81)
+    ///     .map(|i| (Position::from_absolute(i).unwrap(), Player::X))
+    ///     .collect();
+    /// assert_eq!(Board::from_cells(&cells).unwrap().fill_ratio(), 1.0);
+    /// ```
+    pub fn fill_ratio(&self) -> f32 {
+        let played = self.count(Player::X) + self.count(Player::O);
+        played as f32 / 81.0
+    }
+
+    /// Returns, for each small board, the fraction of its tiles that have
+    /// already been played.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// assert_eq!(board.board_fill_ratios(), [0.0; 9]);
+    /// for i in 9..18 {
+    ///     board.play(Player::O, Position::from_absolute(i).unwrap());
+    /// }
+    /// assert_eq!(board.board_fill_ratios()[1], 1.0);
+    /// assert_eq!(board.board_fill_ratios()[2], 0.0);
+    /// ```
+    pub fn board_fill_ratios(&self) -> [f32; 9] {
+        let mut res = [0.0; 9];
+        for (board_idx, ratio) in res.iter_mut().enumerate() {
+            let played = 9 - self.empty_tiles(board_idx);
+            *ratio = played as f32 / 9.0;
+        }
+        res
+    }
+
+    /// Returns a score in `0.0..=1.0` of how close the game is to being decided.
+    ///
+    /// The score is `1.0` once the game was won or every board is closed (see
+    /// `Board::is_decided`), and `0.5 * t + 0.25 * d + 0.25 * f` otherwise,
+    /// where:
+    ///  * `t` is `1.0` if a player owns two boards of a metaboard line and can
+    ///    capture the third one in a single move, `0.5` if a player owns two
+    ///    boards of a metaboard line whose third board is still open, and `0.0`
+    ///    otherwise;
+    ///  * `d` is the fraction of the eight metaboard lines that can no longer
+    ///    be completed by either player;
+    ///  * `f` is the `fill_ratio` of the board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// assert_eq!(Board::new().criticality(), 0.0);
+    ///
+    /// // X owns boards 0 and 1 and threatens to capture board 2
+    /// let cells: Vec<(Position, Player)> = [0, 1, 2, 9, 10, 11, 18, 19].iter()
+    ///     .map(|&i| (Position::from_absolute(i).unwrap(), Player::X))
+    ///     .collect();
+    /// let mut board = Board::from_cells(&cells).unwrap();
+    /// assert!(board.criticality() > 0.5);
+    ///
+    /// // and wins the game
+    /// board.play(Player::X, Position::from_absolute(20).unwrap()).unwrap();
+    /// assert_eq!(board.winner(), Some(Player::X));
+    /// assert_eq!(board.criticality(), 1.0);
+    /// ```
+    pub fn criticality(&self) -> f32 {
+        if self.winner().is_some() || self.is_decided() {
+            return 1.0;
+        }
+
+        let mut threat: f32 = 0.0;
+        let mut dead_lines = 0;
+
+        let metaboard = self.metaboard();
+
+        for line in LINES.iter() {
+            let owned = |player| line.iter().filter(|&&i| metaboard[i] == Some(player)).count();
+            let drawn = line.iter().any(|&i| self.sub_board_result(i) == SubBoardResult::Drawn);
+
+            if drawn || (owned(Player::X) > 0 && owned(Player::O) > 0) {
+                dead_lines += 1;
+                continue;
+            }
+
+            for &player in [Player::X, Player::O].iter() {
+                if owned(player) != 2 {
+                    continue;
+                }
+                let third = line.iter().find(|&&i| metaboard[i].is_none());
+                if let Some(&board_idx) = third {
+                    if self.near_wins(player).contains(&board_idx) {
+                        threat = threat.max(1.0);
+                    } else {
+                        threat = threat.max(0.5);
+                    }
+                }
+            }
+        }
+
+        0.5 * threat + 0.25 * dead_lines as f32 / 8.0 + 0.25 * self.fill_ratio()
+    }
+
+    /// Returns why the game is a tie, or why it is bound to end in a tie
+    /// no matter how it is played. Returns `None` if the game was won or
+    /// can still be won.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, DrawReason, Player, Position};
+    /// use sttt::Player::{X, O};
+    ///
+    /// // wins each given board on its top row
+    /// fn won(owners: &[(usize, Player)]) -> Vec<(Position, Player)> {
+    ///     let mut cells = Vec::new();
+    ///     for &(board_idx, player) in owners {
+    ///         for tile_idx in 0..3 {
+    ///             cells.push((Position::from_absolute(board_idx * 9 + tile_idx).unwrap(), player));
+    ///         }
+    ///     }
+    ///     cells
+    /// }
+    ///
+    /// let owners = [(0, X), (1, O), (2, X), (3, X), (4, O), (5, O), (6, O), (7, X), (8, X)];
+    /// let board = Board::from_cells(&won(&owners)).unwrap();
+    /// assert_eq!(board.draw_reason(), Some(DrawReason::AllBoardsDecidedNoLine));
+    ///
+    /// // board 0 is still open, but every line is blocked
+    /// let owners = [(1, O), (2, X), (3, X), (4, X), (5, O), (6, O), (7, X), (8, O)];
+    /// let board = Board::from_cells(&won(&owners)).unwrap();
+    /// assert_eq!(board.draw_reason(), Some(DrawReason::AllMetaboardLinesBlocked));
+    ///
+    /// // X could still win the top row, but nobody can win board 2
+    /// let owners = [(0, X), (1, X), (3, O), (4, O), (5, X), (6, X), (7, O), (8, O)];
+    /// let mut cells = won(&owners);
+    /// let dead = [X, O, X,
+    ///             X, O, O,
+    ///             O, X];
+    /// for (tile_idx, &player) in dead.iter().enumerate() {
+    ///     cells.push((Position::from_absolute(18 + tile_idx).unwrap(), player));
+    /// }
+    /// let board = Board::from_cells(&cells).unwrap();
+    /// assert_eq!(board.draw_reason(), Some(DrawReason::AllRemainingBoardsDead));
+    ///
+    /// assert_eq!(Board::new().draw_reason(), None);
+    /// ```
+    pub fn draw_reason(&self) -> Option<DrawReason> {
+        if self.winner().is_some() {
+            return None;
+        }
+
+        if self.is_decided() {
+            return Some(DrawReason::AllBoardsDecidedNoLine);
+        }
+        let open: Vec<usize> = (0..9).filter(|&board_idx| self.is_open(board_idx)).collect();
+
+        let blocked = |line: &[usize; 3]| {
+            let drawn = line.iter().any(|&i| self.sub_board_result(i) == SubBoardResult::Drawn);
+            let owns = |player| line.iter().any(|&i| self.owner(i) == Some(player));
+            drawn || (owns(Player::X) && owns(Player::O))
+        };
+        if LINES.iter().all(blocked) {
+            return Some(DrawReason::AllMetaboardLinesBlocked);
+        }
+
+        if open.iter().all(|&board_idx| self.live_lines(board_idx).is_empty()) {
+            return Some(DrawReason::AllRemainingBoardsDead);
+        }
+
+        None
+    }
+
+    /// Returns the index of the last undecided board, if exactly one
+    /// board is still open. Otherwise returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let owners = [Player::X, Player::O, Player::X, Player::X,
+    ///               Player::O, Player::O, Player::O, Player::X];
+    ///
+    /// // every board but the last is won on its top row
+    /// let mut cells = Vec::new();
+    /// for (board_idx, &player) in owners.iter().enumerate() {
+    ///     for tile_idx in 0..3 {
+    ///         let pos = Position::from_absolute(board_idx * 9 + tile_idx).unwrap();
+    ///         cells.push((pos, player));
+    ///     }
+    /// }
+    ///
+    /// let board = Board::from_cells(&cells).unwrap();
+    /// assert_eq!(board.final_board(), Some(8));
+    /// assert_eq!(Board::new().final_board(), None);
+    /// ```
+    pub fn final_board(&self) -> Option<usize> {
+        let mut open = (0..9).filter(|&board_idx| self.is_open(board_idx));
+
+        match (open.next(), open.next()) {
+            (Some(board_idx), None) => Some(board_idx),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if no small board is open, i.e. every small board
+    /// was either won or drawn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// assert!(!Board::new().is_decided());
+    ///
+    /// let cells: Vec<(Position, Player)> = (0..9)
+    ///     .flat_map(|board_idx| (0..3).map(move |tile_idx| board_idx * 9 + tile_idx))
+    ///     .map(|i| (Position::from_absolute(i).unwrap(), Player::X))
+    ///     .collect();
+    /// assert!(Board::from_cells(&cells).unwrap().is_decided());
+    /// ```
+    pub fn is_decided(&self) -> bool {
+        (0..9).all(|board_idx| !self.is_open(board_idx))
+    }
+
+    /// Returns `true` if every tile of every small board has been played.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// assert!(!board.is_full());
+    ///
+    /// board.play(Player::X, Position::from_absolute(40).unwrap());
+    /// assert!(!board.is_full());
+    ///
+    /// let cells: Vec<(Position, Player)> = (0..81)
+    ///     .map(|i| (Position::from_absolute(i).unwrap(), Player::O))
+    ///     .collect();
+    /// assert!(Board::from_cells(&cells).unwrap().is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        (0..9).all(|board_idx| self.empty_tiles(board_idx) == 0)
+    }
+
+    /// Returns the winner of the game, i.e. the player with three won
+    /// boards in a line of the metaboard, if any.
+    ///
+    /// This is the same as calling `Board::check_winner` on the metaboard.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// // X wins boards 2, 4 and 6 on their anti-diagonal
+    /// let cells: Vec<(Position, Player)> = [20, 22, 24, 38, 40, 42, 56, 58, 60].iter()
+    ///     .map(|&i| (Position::from_absolute(i).unwrap(), Player::X))
+    ///     .collect();
+    /// let board = Board::from_cells(&cells).unwrap();
+    ///
+    /// assert_eq!(board.winner(), Some(Player::X));
+    /// assert_eq!(Board::new().winner(), None);
+    /// ```
+    pub fn winner(&self) -> Option<Player> {
+        Board::mask_winner(self.meta_x, self.meta_o)
+    }
+
+    /// Renders the board as a plain 9x9 grid of `X`, `O` and `.` (for empty
+    /// tiles), one line per row and without separators or the metaboard.
+    ///
+    /// Rows and columns follow `Position::global_row` and `Position::global_col`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_absolute(0).unwrap());
+    /// board.play(Player::O, Position::from_absolute(40).unwrap());
+    /// board.play(Player::X, Position::from_absolute(80).unwrap());
+    /// board.play(Player::O, Position::from_absolute(20).unwrap());
+    ///
+    /// assert_eq!(board.to_ascii_grid(), "\
+    /// X.......O
+    /// .........
+    /// .........
+    /// .........
+    /// ....O....
+    /// .........
+    /// .........
+    /// .........
+    /// ........X");
+    /// ```
+    pub fn to_ascii_grid(&self) -> String {
+        let mut rows = Vec::with_capacity(9);
+
+        for row in 0..9 {
+            let mut line = String::with_capacity(9);
+            for col in 0..9 {
+                let board_idx = (row / 3) * 3 + col / 3;
+                let tile_idx = (row % 3) * 3 + col % 3;
+                match self.get(board_idx, tile_idx) {
+                    None => line.push('.'),
+                    Some(p) => line.push_str(&p.to_string()),
+                }
+            }
+            rows.push(line);
+        }
+
+        rows.join("\n")
+    }
+
+    /// Renders the board with Unicode box-drawing characters.
+    ///
+    /// The layout is the same as the one of `Display`, without the metaboard:
+    /// small boards are drawn with thin lines and the big boards are split
+    /// by thick ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_absolute(0).unwrap());
+    ///
+    /// let rendered = board.to_unicode();
+    /// let lines: Vec<Vec<char>> = rendered.lines().map(|l| l.chars().collect()).collect();
+    /// assert_eq!(lines.len(), 23);
+    /// assert_eq!(lines[0][15], '┃');
+    /// assert_eq!(lines[1][3], 'X');
+    /// assert_eq!(lines[1][5], '│');
+    /// assert_eq!(lines[2][5], '┼');
+    /// assert_eq!(lines[7][15], '╋');
+    /// assert_eq!(lines[7][0], '━');
+    /// ```
+    pub fn to_unicode(&self) -> String {
+        const BIG_ROW_EMPTY: &str = "               ┃               ┃";
+        const BIG_ROW_SEP: &str   = "━━━━━━━━━━━━━━━╋━━━━━━━━━━━━━━━╋━━━━━━━━━━━━━━━";
+        const SMALL_ROW_SEP: &str =   "───┼───┼───";
+
+        let mut res = String::new();
+
+        for big_row in 0..3 {
+            res.push_str(BIG_ROW_EMPTY);
+            res.push('\n');
+
+            for small_row in 0..3 {
+                for big_col in 0..3 {
+                    res.push_str("  ");
+
+                    for small_col in 0..3 {
+                        let board_idx = big_row * 3 + big_col;
+                        let position_idx = small_row * 3 + small_col;
+
+                        match self.get(board_idx, position_idx) {
+                            None => res.push_str("   "),
+                            Some(p) => res.push_str(&format!(" {} ", p)[..]),
+                        };
+
+                        if small_col < 2 {
+                            res.push('│');
+                        }
+                    }
+
+                    if big_col < 2 {
+                        res.push_str("  ┃");
+                    }
+                }
+
+                if small_row < 2 {
+                    res.push('\n');
+                    for big_col in 0..3 {
+                        res.push_str("  ");
+                        res.push_str(SMALL_ROW_SEP);
+                        if big_col < 2 {
+                            res.push_str("  ┃");
+                        }
+                    }
+                }
+                res.push('\n');
+            }
+
+            res.push_str(BIG_ROW_EMPTY);
+            res.push('\n');
+
+            if big_row < 2 {
+                res.push_str(BIG_ROW_SEP);
+                res.push('\n');
+            }
+        }
+
+        res
+    }
+
+    /// Renders the board in the same layout as its `Display` implementation,
+    /// marking the given boards with a line of `*` above and below them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Board;
+    ///
+    /// let board = Board::new();
+    /// assert_eq!(board.render_with_highlight(&[]), board.to_string());
+    ///
+    /// let rendered = board.render_with_highlight(&[0, 5]);
+    /// let lines: Vec<&str> = rendered.lines().collect();
+    /// assert_eq!(lines[0], "  ***********  |               |");
+    /// assert_eq!(lines[8], "               |               |  ***********");
+    /// ```
+    pub fn render_with_highlight(&self, highlighted: &[usize]) -> String {
+        self.render(highlighted, false, false)
+    }
+
+    /// Renders the board in the same layout as its `Display` implementation,
+    /// painting `X` in red and `O` in blue with ANSI escape codes. Tiles of
+    /// boards that were already won are dimmed.
+    ///
+    /// Colors are only used when the `colored` feature is enabled. Otherwise,
+    /// this is the same as the `Display` output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, Position::from_absolute(40).unwrap());
+    ///
+    /// let rendered = board.render_colored();
+    /// if cfg!(feature = "colored") {
+    ///     assert!(rendered.contains("\x1b[31mX\x1b[0m"));
+    /// } else {
+    ///     assert!(!rendered.contains('\x1b'));
+    ///     assert_eq!(rendered, board.to_string());
+    /// }
+    /// ```
+    pub fn render_colored(&self) -> String {
+        self.render(&[], cfg!(feature = "colored"), false)
+    }
+
+    /// Renders the board like `Display`, with empty tiles showing their
+    /// absolute position and open boards their index in the metaboard
+    pub(crate) fn render_labels(&self) -> String {
+        self.render(&[], false, true)
+    }
+
+    fn render(&self, highlighted: &[usize], colored: bool, labels: bool) -> String {
+        const HIGHLIGHT: &str     = "  ***********  ";
+        const NO_HIGHLIGHT: &str  = "               ";
         const BIG_ROW_SEP: &str   = "---------------+---------------+---------------";
         const SMALL_ROW_SEP: &str =   "---+---+---";
         const METABOARD_SEP: &str = "              ";
 
+        // empty line above and below a big row, marking the highlighted boards
+        let big_row_empty = |big_row: usize| {
+            let mut line = String::new();
+            for big_col in 0..3 {
+                if highlighted.contains(&(big_row * 3 + big_col)) {
+                    line.push_str(HIGHLIGHT);
+                } else {
+                    line.push_str(NO_HIGHLIGHT);
+                }
+                if big_col < 2 {
+                    line.push('|');
+                }
+            }
+            line.trim_end().to_string()
+        };
+
+        // a played tile, dimmed if it is part of a won board
+        let tile = |player: Player, dim: bool| {
+            if !colored {
+                return format!(" {} ", player);
+            }
+            let color = match player {
+                Player::X => "31",
+                Player::O => "34",
+            };
+            let dim = if dim { "2;" } else { "" };
+            format!(" \x1b[{}{}m{}\x1b[0m ", dim, color, player)
+        };
+
         let mut res = String::new();
 
         for big_row in 0..3 {
-            res.push_str(BIG_ROW_EMPTY);
+            res.push_str(&big_row_empty(big_row));
             res.push('\n');
 
             for small_row in 0..3 {
@@ -309,9 +1589,10 @@ impl fmt::Display for Board {
                         let board_idx = big_row * 3 + big_col;
                         let position_idx = small_row * 3 + small_col;
                         
-                        match self.board[board_idx][position_idx] {
+                        match self.get(board_idx, position_idx) {
+                            None if labels => res.push_str(&format!(" {:<2}", board_idx * 9 + position_idx)),
                             None => res.push_str("   "),
-                            Some(p) => res.push_str(&format!(" {} ", p)[..]),
+                            Some(p) => res.push_str(&tile(p, self.owner(board_idx).is_some())),
                         };
 
                         if small_col < 2 {
@@ -330,9 +1611,11 @@ impl fmt::Display for Board {
                     res.push_str(METABOARD_SEP);
                     for small_col in 0..3 {
                         let idx = small_row * 3 + small_col;
-                        match self.metaboard[idx] {
-                            None => res.push_str("   "),
-                            Some(p) => res.push_str(&format!(" {} ", p)[..]),
+                        match self.sub_board_result(idx) {
+                            SubBoardResult::Open if labels => res.push_str(&format!(" {} ", idx)),
+                            SubBoardResult::Open => res.push_str("   "),
+                            SubBoardResult::Won(p) => res.push_str(&tile(p, false)),
+                            SubBoardResult::Drawn => res.push_str(" - "),
                         };
                         if small_col < 2 {
                             res.push('|');
@@ -361,7 +1644,7 @@ impl fmt::Display for Board {
                 res.push('\n');
             }
 
-            res.push_str(BIG_ROW_EMPTY);
+            res.push_str(&big_row_empty(big_row));
             res.push('\n');
 
             if big_row < 2 {
@@ -376,7 +1659,346 @@ impl fmt::Display for Board {
             }
         }
 
-        write!(f, "{}", res)
+        res
+    }
+
+    /// Tic-Tac-Toe logic to check if a 3x3 board has a winner
+    ///
+    /// # Examples
+    /// 
+    /// ```
+    /// use sttt::{Board, Player};
+    ///
+    /// let mut ttt: [Option<Player>; 9] = [None; 9];
+    /// ttt[0] = Some(Player::X);
+    /// ttt[1] = Some(Player::X);
+    /// assert_eq!(Board::check_winner(&ttt),  None);
+    /// ttt[2] = Some(Player::X);
+    /// assert_eq!(Board::check_winner(&ttt),  Some(Player::X));
+    /// ```
+    ///
+    /// The bitmask implementation agrees with checking rows, columns and
+    /// diagonals tile by tile, on arbitrary boards:
+    ///
+    /// ```
+    /// use sttt::{Board, Player};
+    ///
+    /// fn by_tiles(b: &[Option<Player>; 9]) -> Option<Player> {
+    ///     for i in 0..3 {
+    ///         if b[i * 3].is_some() && b[i * 3] == b[i * 3 + 1] && b[i * 3] == b[i * 3 + 2] {
+    ///             return b[i * 3];
+    ///         }
+    ///     }
+    ///     for i in 0..3 {
+    ///         if b[i].is_some() && b[i] == b[i + 3] && b[i] == b[i + 6] {
+    ///             return b[i];
+    ///         }
+    ///     }
+    ///     if b[4].is_some() && ((b[0] == b[4] && b[4] == b[8]) || (b[2] == b[4] && b[4] == b[6])) {
+    ///         return b[4];
+    ///     }
+    ///     None
+    /// }
+    ///
+    /// let mut seed: u64 = 0x2545_F491_4F6C_DD1D;
+    /// for _ in 0..10_000 {
+    ///     let mut ttt = [None; 9];
+    ///     for tile in ttt.iter_mut() {
+    ///         seed ^= seed << 13;
+    ///         seed ^= seed >> 7;
+    ///         seed ^= seed << 17;
+    ///         *tile = [None, Some(Player::X), Some(Player::O)][(seed % 3) as usize];
+    ///     }
+    ///     assert_eq!(Board::check_winner(&ttt), by_tiles(&ttt));
+    /// }
+    /// ```
+    ///
+    /// A center tile alone doesn't make a diagonal, and every winning line
+    /// wins only when all three of its tiles belong to the same player:
+    ///
+    /// ```
+    /// use sttt::{Board, Player};
+    /// use sttt::Player::{X, O};
+    ///
+    /// // center taken, corners mismatched or empty
+    /// let diagonals = [
+    ///     [Some(X), None, Some(O),  None, Some(X), None,  Some(O), None, None],
+    ///     [Some(O), None, Some(X),  None, Some(X), None,  None, None, Some(O)],
+    ///     [None, None, None,  None, Some(X), None,  None, None, None],
+    ///     [Some(X), None, None,  None, Some(X), None,  Some(X), None, None],
+    /// ];
+    /// for ttt in diagonals.iter() {
+    ///     assert_eq!(Board::check_winner(ttt), None);
+    /// }
+    ///
+    /// let lines = [[0, 1, 2], [3, 4, 5], [6, 7, 8],
+    ///              [0, 3, 6], [1, 4, 7], [2, 5, 8],
+    ///              [0, 4, 8], [2, 4, 6]];
+    /// for line in lines.iter() {
+    ///     for &player in &[X, O] {
+    ///         let other = player.opponent();
+    ///
+    ///         // each of the 8 fillings of the line with two players
+    ///         for filling in 0..8 {
+    ///             let mut ttt: [Option<Player>; 9] = [None; 9];
+    ///             for (bit, &tile) in line.iter().enumerate() {
+    ///                 ttt[tile] = Some(if filling & (1 << bit) == 0 { player } else { other });
+    ///             }
+    ///             let expected = match filling {
+    ///                 0 => Some(player),
+    ///                 7 => Some(other),
+    ///                 _ => None,
+    ///             };
+    ///             assert_eq!(Board::check_winner(&ttt), expected, "{:?} {}", line, filling);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn check_winner(board: &[Option<Player>;9]) -> Option<Player> {
+        Board::winning_line(board).map(|(player, _)| player)
+    }
+
+    /// Tic-Tac-Toe logic for a `size`x`size` board, where a player needs a
+    /// full row, column or diagonal (`size` tiles in a line) to win.
+    ///
+    /// Tiles are in row-major order. With `size` equal to `3`, this is the
+    /// same as `Board::check_winner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board doesn't have `size * size` tiles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player};
+    /// use sttt::Player::{X, O};
+    ///
+    /// let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+    /// for _ in 0..1_000 {
+    ///     let mut ttt = [None; 9];
+    ///     for tile in ttt.iter_mut() {
+    ///         seed ^= seed << 13;
+    ///         seed ^= seed >> 7;
+    ///         seed ^= seed << 17;
+    ///         *tile = [None, Some(X), Some(O)][(seed % 3) as usize];
+    ///     }
+    ///     assert_eq!(Board::check_winner_n(&ttt, 3), Board::check_winner(&ttt));
+    /// }
+    ///
+    /// let mut board: [Option<Player>; 16] = [None; 16];
+    /// // three in a row isn't enough on a 4x4 board
+    /// for &i in &[4, 5, 6] {
+    ///     board[i] = Some(X);
+    /// }
+    /// assert_eq!(Board::check_winner_n(&board, 4), None);
+    /// board[7] = Some(X);
+    /// assert_eq!(Board::check_winner_n(&board, 4), Some(X));
+    ///
+    /// let mut board: [Option<Player>; 16] = [None; 16];
+    /// for &i in &[3, 6, 9, 12] {
+    ///     board[i] = Some(O);
+    /// }
+    /// assert_eq!(Board::check_winner_n(&board, 4), Some(O));
+    /// ```
+    pub fn check_winner_n(board: &[Option<Player>], size: usize) -> Option<Player> {
+        assert_eq!(board.len(), size * size);
+
+        let line = |tiles: Vec<usize>| {
+            let first = board[*tiles.first()?];
+            if first.is_some() && tiles.iter().all(|&i| board[i] == first) {
+                first
+            } else {
+                None
+            }
+        };
+
+        let rows = (0..size).map(|row| line((0..size).map(|col| row * size + col).collect()));
+        let cols = (0..size).map(|col| line((0..size).map(|row| row * size + col).collect()));
+        let diagonal = line((0..size).map(|i| i * size + i).collect());
+        let anti_diagonal = line((0..size).map(|i| i * size + size - 1 - i).collect());
+
+        rows.chain(cols)
+            .chain(vec![diagonal, anti_diagonal])
+            .find_map(|winner| winner)
+    }
+
+    /// Returns the winner of a 3x3 board together with the tiles of the line
+    /// they completed, for instance to highlight it. The tiles are in
+    /// increasing order.
+    ///
+    /// This works both for small boards, as returned by `Board::sub_board`,
+    /// and for the metaboard.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    /// use sttt::Player::{X, O};
+    ///
+    /// let line = |tiles: &[usize], player| {
+    ///     let mut ttt = [None; 9];
+    ///     for &tile in tiles {
+    ///         ttt[tile] = Some(player);
+    ///     }
+    ///     Board::winning_line(&ttt)
+    /// };
+    ///
+    /// assert_eq!(line(&[3, 4, 5], X), Some((X, [3, 4, 5])));
+    /// assert_eq!(line(&[1, 4, 7], O), Some((O, [1, 4, 7])));
+    /// assert_eq!(line(&[0, 4, 8], X), Some((X, [0, 4, 8])));
+    /// assert_eq!(line(&[2, 4, 6], O), Some((O, [2, 4, 6])));
+    /// assert_eq!(line(&[0, 4, 5], X), None);
+    ///
+    /// // X wins boards 2, 4 and 6, the first one on its middle column
+    /// let cells: Vec<(Position, Player)> = [19, 22, 25, 36, 40, 44, 56, 58, 60].iter()
+    ///     .map(|&i| (Position::from_absolute(i).unwrap(), X))
+    ///     .collect();
+    /// let board = Board::from_cells(&cells).unwrap();
+    /// assert_eq!(Board::winning_line(&board.sub_board(2)), Some((X, [1, 4, 7])));
+    /// assert_eq!(Board::winning_line(&board.metaboard()), Some((X, [2, 4, 6])));
+    /// ```
+    pub fn winning_line(board: &[Option<Player>;9]) -> Option<(Player, [usize; 3])> {
+        let mut x = 0;
+        let mut o = 0;
+        for (tile_idx, tile) in board.iter().enumerate() {
+            match tile {
+                Some(Player::X) => x |= 1 << tile_idx,
+                Some(Player::O) => o |= 1 << tile_idx,
+                None => {},
+            }
+        }
+
+        Board::mask_line(x, o).map(|(player, line_idx)| (player, LINES[line_idx]))
+    }
+
+    /// Returns `true` if the mask has every tile of some line set
+    fn has_line(mask: u16) -> bool {
+        LINE_MASKS.iter().any(|&line| line & !mask == 0)
+    }
+
+    /// Bitmask version of `check_winner`, given the tiles of each player
+    fn mask_winner(x: u16, o: u16) -> Option<Player> {
+        Board::mask_line(x, o).map(|(player, _)| player)
+    }
+
+    /// Returns the winner and the index in `LINES` of their line, given the
+    /// tiles of each player
+    fn mask_line(x: u16, o: u16) -> Option<(Player, usize)> {
+        LINE_MASKS.iter().enumerate().find_map(|(line_idx, &line)| {
+            if x & line == line {
+                Some((Player::X, line_idx))
+            } else if o & line == line {
+                Some((Player::O, line_idx))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Width, in characters, of the widest line produced by `Display for Board`
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{Board, BOARD_DISPLAY_WIDTH, BOARD_DISPLAY_HEIGHT};
+///
+/// let rendered = Board::new().to_string();
+/// let widest = rendered.lines().map(|l| l.chars().count()).max();
+/// assert_eq!(widest, Some(BOARD_DISPLAY_WIDTH));
+/// assert_eq!(rendered.lines().count(), BOARD_DISPLAY_HEIGHT);
+/// ```
+pub const BOARD_DISPLAY_WIDTH: usize = 70;
+
+/// Number of lines produced by `Display for Board`
+pub const BOARD_DISPLAY_HEIGHT: usize = 23;
+
+impl Default for Board {
+    /// Same as `Board::new()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Board;
+    ///
+    /// fn fresh<T: Default>() -> T { T::default() }
+    ///
+    /// assert!(fresh::<Board>() == Board::new());
+    /// ```
+    fn default() -> Self { Board::new() }
+}
+
+impl fmt::Display for Board {
+    /*
+     *                 |               |
+     *     0 | 1 | 2   |   9 | 10| 11  |   18| 19| 20
+     *    ---+---+---  |  ---+---+---  |  ---+---+--- 
+     *     3 | 4 | 5   |   12| 13| 14  |   21| 22| 23
+     *    ---+---+---  |  ---+---+---  |  ---+---+---
+     *     6 | 7 | 8   |   15| 16| 17  |   24| 25| 26
+     *                 |               |
+     *  ---------------+---------------+---------------             Meta Board:
+     *                 |               | 
+     *     27| 28| 29  |   36| 37| 38  |   45| 46| 47                0 | 1 | 2
+     *    ---+---+---  |  ---+---+---  |  ---+---+---               ---+---+---
+     *     30| 31| 32  |   39| 40| 41  |   48| 49| 50                3 | 4 | 5
+     *    ---+---+---  |  ---+---+---  |  ---+---+---               ---+---+---
+     *     33| 34| 35  |   42| 43| 44  |   51| 52| 53                6 | 7 | 8
+     *                 |               |
+     *  ---------------+---------------+---------------
+     *                 |               |
+     *     54| 55| 56  |   63| 64| 65  |   72| 73| 74
+     *    ---+---+---  |  ---+---+---  |  ---+---+---
+     *     57| 58| 59  |   66| 67| 68  |   75| 76| 77
+     *    ---+---+---  |  ---+---+---  |  ---+---+---
+     *     60| 61| 62  |   69| 70| 71  |   78| 79| 80
+     *                 |               |
+     *
+     */
+    /// Displays the board with the metaboard on its right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, Player, Position};
+    ///
+    /// let cells: Vec<(Position, Player)> = [
+    ///     (0, Player::O), (4, Player::O), (8, Player::O), (20, Player::O),
+    ///     (36, Player::X), (40, Player::X), (44, Player::X), (41, Player::O),
+    ///     (72, Player::X),
+    /// ].iter().map(|&(i, p)| (Position::from_absolute(i).unwrap(), p)).collect();
+    /// let board = Board::from_cells(&cells).unwrap();
+    ///
+    /// let expected = [
+    ///     "               |               |",
+    ///     "   O |   |     |     |   |     |     |   | O ",
+    ///     "  ---+---+---  |  ---+---+---  |  ---+---+---",
+    ///     "     | O |     |     |   |     |     |   |   ",
+    ///     "  ---+---+---  |  ---+---+---  |  ---+---+---",
+    ///     "     |   | O   |     |   |     |     |   |   ",
+    ///     "               |               |",
+    ///     "---------------+---------------+---------------             metaboard",
+    ///     "               |               |",
+    ///     "     |   |     |   X |   |     |     |   |                  O |   |   ",
+    ///     "  ---+---+---  |  ---+---+---  |  ---+---+---              ---+---+---",
+    ///     "     |   |     |     | X | O   |     |   |                    | X |   ",
+    ///     "  ---+---+---  |  ---+---+---  |  ---+---+---              ---+---+---",
+    ///     "     |   |     |     |   | X   |     |   |                    |   |   ",
+    ///     "               |               |",
+    ///     "---------------+---------------+---------------",
+    ///     "               |               |",
+    ///     "     |   |     |     |   |     |   X |   |   ",
+    ///     "  ---+---+---  |  ---+---+---  |  ---+---+---",
+    ///     "     |   |     |     |   |     |     |   |   ",
+    ///     "  ---+---+---  |  ---+---+---  |  ---+---+---",
+    ///     "     |   |     |     |   |     |     |   |   ",
+    ///     "               |               |",
+    /// ];
+    /// assert_eq!(board.to_string(), expected.join("\n") + "\n");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render_with_highlight(&[]))
     }
 }
 
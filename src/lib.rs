@@ -3,17 +3,41 @@
 //! A library crate that contains the logic of Super Tic-Tac-Toe!
 
 use std::collections::HashSet;
+use std::error::Error;
 use std::fmt;
 
 mod board;
+mod zobrist;
+pub mod ai;
 
-pub use board::{Board,Position};
+pub use board::{Board,Position,DrawReason,SubBoardResult};
+pub use board::{BOARD_DISPLAY_WIDTH, BOARD_DISPLAY_HEIGHT};
+pub use zobrist::ZobristHasher;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 /// Represents the possible players in a 
 /// Super Tic-Tac-Toe game: `X` and `O`.
 pub enum Player { X, O }
 
+impl Player {
+    /// Returns the other player.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::Player;
+    ///
+    /// assert_eq!(Player::X.opponent(), Player::O);
+    /// assert_eq!(Player::O.opponent(), Player::X);
+    /// ```
+    pub fn opponent(self) -> Player {
+        match self {
+            Player::X => Player::O,
+            Player::O => Player::X,
+        }
+    }
+}
+
 impl fmt::Display for Player {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -23,7 +47,18 @@ impl fmt::Display for Player {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 /// Represents the possible statuses of a game.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{Player, Status};
+///
+/// assert_eq!(Status::Winner(Player::X), Status::Winner(Player::X));
+/// assert_ne!(Status::Winner(Player::X), Status::Winner(Player::O));
+/// assert_ne!(Status::Tie, Status::InProgress);
+/// ```
 pub enum Status {
     /// Represents that `Player` has won the game.
     Winner(Player),
@@ -33,11 +68,251 @@ pub enum Status {
     InProgress,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// Represents the reasons why a play can be rejected.
+pub enum PlayError {
+    /// The player tried to play in the other player's turn.
+    NotYourTurn,
+    /// The position is in a board the player is not allowed to play in.
+    BoardNotAllowed,
+    /// The position was already played.
+    CellOccupied,
+    /// The position is outside of the board.
+    OutOfBounds,
+    /// There are no moves to undo.
+    NothingToUndo,
+    /// The game was already won or tied.
+    GameOver,
+    /// The position can't be reached by playing.
+    InvalidPosition,
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PlayError::NotYourTurn => write!(f, "It's not your turn!"),
+            PlayError::BoardNotAllowed => write!(f, "You cannot play in that board!"),
+            PlayError::CellOccupied => write!(f, "That square is not empty"),
+            PlayError::OutOfBounds => write!(f, "Position outside of board"),
+            PlayError::NothingToUndo => write!(f, "There are no moves to undo"),
+            PlayError::GameOver => write!(f, "The game is over"),
+            PlayError::InvalidPosition => write!(f, "That position can't be reached"),
+        }
+    }
+}
+
+impl Error for PlayError {}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// Represents an inconsistency found in the state of a game.
+pub enum StateProblem {
+    /// `X` played this many more tiles than `O`, which can't happen unless
+    /// it is `0` or `1` in a game started by `X`, or `0` or `-1` in a game
+    /// started by `O`.
+    PieceImbalance(i32),
+    /// The winner in the metaboard for this board has no line in it, or
+    /// nobody won it but someone has a line.
+    MetaboardMismatch(usize),
+    /// This board is valid to play in, but it was already won or filled.
+    WonBoardStillActive(usize),
+    /// This valid board index is outside of the metaboard.
+    IllegalValidBoard(usize),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// Represents whether a tile can be played in, and why not.
+pub enum CellLegality {
+    /// The next player can play in the tile.
+    Legal,
+    /// The tile has already been played.
+    Occupied,
+    /// The tile is in a board the next player is not allowed to play in.
+    WrongBoard,
+    /// The tile is in a board that was already won or filled.
+    BoardDecided,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// A copy of the state of a game at some point, e.g. to stream each move to
+/// spectators.
+pub struct GameSnapshot {
+    /// The player on each tile, indexed by absolute position.
+    pub cells: Vec<Option<Player>>,
+    /// The winner of each small board.
+    pub metaboard: [Option<Player>; 9],
+    /// The next player to play.
+    pub player: Player,
+    /// The boards the next player can play in, in increasing order. Empty
+    /// once the game is over.
+    pub valid_boards: Vec<usize>,
+    /// The status of the game.
+    pub status: Status,
+}
+
+impl GameSnapshot {
+    /// Writes the snapshot as a single line of JSON, without a trailing
+    /// newline, so that one snapshot per move makes a JSON Lines stream.
+    ///
+    /// Players are written as `"X"` and `"O"`, and empty tiles or boards
+    /// nobody won as `null`. The status is `"in_progress"`, `"tie"` or
+    /// `"won"`, with the winner in the `winner` field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play_move(Position::from_absolute(40).unwrap()).unwrap();
+    ///
+    /// let json = game.snapshot().to_json();
+    /// assert!(json.starts_with(r#"{"cells":[null,null,"#));
+    /// assert!(json.contains(r#","X","#));
+    /// assert!(json.ends_with(r#""player":"O","valid_boards":[4],"status":"in_progress","winner":null}"#));
+    /// assert!(!json.contains('\n'));
+    /// ```
+    pub fn to_json(&self) -> String {
+        let player = |p: Option<Player>| match p {
+            Some(p) => format!("\"{}\"", p),
+            None => String::from("null"),
+        };
+        let list = |items: Vec<String>| format!("[{}]", items.join(","));
+
+        let (status, winner) = match self.status {
+            Status::Winner(p) => ("won", Some(p)),
+            Status::Tie => ("tie", None),
+            Status::InProgress => ("in_progress", None),
+        };
+
+        format!(
+            "{{\"cells\":{},\"metaboard\":{},\"player\":{},\"valid_boards\":{},\"status\":\"{}\",\"winner\":{}}}",
+            list(self.cells.iter().map(|&p| player(p)).collect()),
+            list(self.metaboard.iter().map(|&p| player(p)).collect()),
+            player(Some(self.player)),
+            list(self.valid_boards.iter().map(|b| b.to_string()).collect()),
+            status,
+            player(winner),
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+/// Represents where a player may play after being sent to a board that was
+/// already won or filled.
+pub enum SendToClosed {
+    /// The player may play in any open board.
+    #[default]
+    FreeChoice,
+    /// The opponent, who sent the player there, picks an open board with
+    /// `STTT::choose_board`.
+    OpponentChooses,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+/// Represents the rule variants a game is played with.
+///
+/// The default rules are the standard ones.
+pub struct RuleSet {
+    /// Where a player may play after being sent to a closed board.
+    pub send_to_closed: SendToClosed,
+    /// Whether won boards can still be played in until they are full. Won
+    /// boards keep their winner either way.
+    pub play_in_won_boards: bool,
+    /// Whether completing a line in a small board gives it to the opponent
+    /// instead, in the misère variant. The game is still won with a line of
+    /// boards.
+    pub misere: bool,
+}
+
+impl RuleSet {
+    /// Returns `true` if the given board can still be played in under these
+    /// rules
+    fn is_open(&self, board: &Board, board_idx: usize) -> bool {
+        if self.play_in_won_boards {
+            board.empty_tiles(board_idx) > 0
+        } else {
+            board.is_open(board_idx)
+        }
+    }
+
+    /// Plays the move on the board, giving the small board to the opponent
+    /// if the move wins it and `misere` is set
+    fn play(&self, board: &mut Board, player: Player, position: Position) -> Result<(), &'static str> {
+        let board_idx = position.board_idx();
+        let was_won = board.metaboard()[board_idx].is_some();
+
+        board.play(player, position)?;
+        if self.misere && !was_won && board.metaboard()[board_idx].is_some() {
+            board.set_owner(board_idx, player.opponent());
+        }
+
+        Ok(())
+    }
+
+    /// Empties the tile at the given position, like `Board::clear`. With
+    /// `misere`, a won board stays with its owner while the opponent who
+    /// gave it away still has a line in it.
+    fn clear(&self, board: &mut Board, position: Position) {
+        let board_idx = position.board_idx();
+        let owner = board.metaboard()[board_idx];
+
+        board.clear(position);
+        if let (true, Some(owner)) = (self.misere, owner) {
+            if STTT::has_line(&board.sub_board(board_idx), owner.opponent()) {
+                board.set_owner(board_idx, owner);
+            } else if let Some(winner) = board.metaboard()[board_idx] {
+                board.set_owner(board_idx, winner.opponent());
+            }
+        }
+    }
+}
 
+/// A game of Super Tic-Tac-Toe: the board, whose turn it is, where they may
+/// play and the moves played so far.
+///
+/// Cloning a game copies all of it except the `STTT::set_on_subboard_won`
+/// callback, so moves can be tried on the copy without affecting the
+/// original.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, Player, Position};
+///
+/// let mut game = STTT::new();
+/// game.play_move(Position::from_absolute(40).unwrap()).unwrap();
+///
+/// let mut preview = game.clone();
+/// preview.play_move(Position::from_absolute(36).unwrap()).unwrap();
+///
+/// assert_eq!(preview.history().len(), 2);
+/// assert_eq!(game.history().len(), 1);
+/// assert_eq!(game.player(), Player::O);
+/// assert_eq!(game.board().get(4, 0), None);
+/// assert_eq!(game.valid_boards(), vec![4]);
+/// ```
 pub struct STTT {
     player: Player,
     board: Board,
     valid_boards: HashSet<usize>,
+    history: Vec<(Player, Position)>,
+    rules: RuleSet,
+    // Zobrist hash of the board and next player
+    zobrist: u64,
+    on_subboard_won: Option<Box<dyn FnMut(usize, Player) + Send>>,
+}
+
+/// A move of the history, with how it was played
+#[derive(Copy, Clone)]
+struct MoveRecord {
+    player: Player,
+    position: Position,
+    // Whether the player could only play in this board
+    forced: bool,
+    // Whether the move won its board
+    captured: bool,
+    // The board after the move
+    board: Board,
 }
 
 impl STTT {
@@ -53,6 +328,49 @@ impl STTT {
     /// let mut game = STTT::new();
     /// ```
     pub fn new() -> STTT {
+        STTT::with_rules(RuleSet::default())
+    }
+
+    /// Creates a new Super Tic-Tac-Toe game, like `STTT::new()`, where the
+    /// given player plays first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, PlayError};
+    ///
+    /// let mut game = STTT::new_with_starting_player(Player::O);
+    /// assert_eq!(game.player(), Player::O);
+    ///
+    /// let pos = Position::from_absolute(40).unwrap();
+    /// assert_eq!(game.play(Player::X, pos), Err(PlayError::NotYourTurn));
+    /// game.play(Player::O, pos).unwrap();
+    /// assert_eq!(game.diagnose(), vec![]);
+    /// game.play(Player::X, Position::from_absolute(36).unwrap()).unwrap();
+    /// assert_eq!(game.player(), Player::O);
+    /// ```
+    pub fn new_with_starting_player(player: Player) -> STTT {
+        let mut game = STTT::new();
+        if player != game.player {
+            game.player = player;
+            game.zobrist ^= zobrist::DEFAULT.side_key();
+        }
+        game
+    }
+
+    /// Creates a new Super Tic-Tac-Toe game, played with the given rules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, RuleSet, SendToClosed};
+    ///
+    /// let rules = RuleSet { send_to_closed: SendToClosed::OpponentChooses, ..RuleSet::default() };
+    /// let game = STTT::with_rules(rules);
+    /// assert_eq!(game.rules(), rules);
+    /// assert_eq!(STTT::new().rules(), RuleSet::default());
+    /// ```
+    pub fn with_rules(rules: RuleSet) -> STTT {
         let mut valid_boards = HashSet::new();
         // in the beginning, every board is valid!
         for i in 0..9 {
@@ -62,7 +380,92 @@ impl STTT {
             player: Player::X,
             board: Board::new(),
             valid_boards,
+            history: Vec::new(),
+            rules,
+            zobrist: 0,
+            on_subboard_won: None,
+        }
+    }
+
+    /// Restarts the game, leaving it in the same state as `STTT::new()`.
+    /// The rules are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    /// game.play(Player::O, Position::from_absolute(36).unwrap()).unwrap();
+    ///
+    /// game.reset();
+    /// assert_eq!(game.player(), Player::X);
+    /// assert_eq!(game.encode(), STTT::new().encode());
+    /// ```
+    pub fn reset(&mut self) {
+        self.player = Player::X;
+        self.board.reset();
+        self.valid_boards.clear();
+        self.valid_boards.extend(0..9);
+        self.history.clear();
+        self.zobrist = 0;
+    }
+
+    /// Creates a new game and plays the given moves, alternating players
+    /// starting with `X`.
+    ///
+    /// For a legal game, this is the inverse of `STTT::history_positions`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `PlayError` of the first illegal move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let moves: Vec<Position> = [40, 36, 4].iter()
+    ///     .map(|&i| Position::from_absolute(i).unwrap())
+    ///     .collect();
+    /// let game = STTT::from_moves(&moves).unwrap();
+    ///
+    /// let copy = STTT::from_moves(&game.history_positions()).unwrap();
+    /// assert_eq!(copy.encode(), game.encode());
+    /// assert_eq!(copy.history(), game.history());
+    /// ```
+    pub fn from_moves(moves: &[Position]) -> Result<STTT, PlayError> {
+        let mut game = STTT::new();
+        game.replay(moves)?;
+        Ok(game)
+    }
+
+    /// Creates a new game where `rng` plays `target_moves` random legal
+    /// moves, or fewer if the game ends sooner.
+    ///
+    /// The same seed always gives the same position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, ai::RandomBot};
+    ///
+    /// let game = STTT::random_position(10, &mut RandomBot::new(1));
+    /// assert_eq!(game.move_count(), 10);
+    /// assert!(game.diagnose().is_empty());
+    /// ```
+    pub fn random_position(target_moves: usize, rng: &mut ai::RandomBot) -> STTT {
+        let mut game = STTT::new();
+
+        while game.move_count() < target_moves {
+            match rng.choose(&game) {
+                Some(position) => { game.play_move(position).unwrap(); },
+                None => break,
+            }
         }
+
+        game
     }
 
     /// Returns the next player to play
@@ -84,21 +487,153 @@ impl STTT {
     /// Returns a copy of the game board
     pub fn board(&self) -> Board { self.board }
 
-    /// Makes player play at a given position.
+    /// Returns a reference to the game board, avoiding the copy made by
+    /// `STTT::board`.
     ///
-    /// Returns the game `Status` resulting from this play in case of success.
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
     ///
-    /// The next player to make a move swaps at each successful call to this function.
+    /// let mut game = STTT::new();
+    /// let before = game.board();
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
     ///
-    /// # Errors
+    /// let board = game.board_ref();
+    /// assert_eq!(board.get(4, 4), Some(Player::X));
+    /// assert_eq!(before.get(4, 4), None);
+    /// assert!(*board == game.board());
+    /// ```
+    pub fn board_ref(&self) -> &Board { &self.board }
+
+    /// Renders an empty board where each tile shows its absolute position,
+    /// and each board of the metaboard its index, to explain which number
+    /// plays where.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::STTT;
+    ///
+    /// let help = STTT::render_help();
+    /// let lines: Vec<&str> = help.lines().collect();
+    ///
+    /// assert_eq!(lines[1], "   0 | 1 | 2   |   9 | 10| 11  |   18| 19| 20");
+    /// // the center tile of the center board
+    /// assert!(lines[11][16..31].contains("40"));
+    /// assert!(lines[11].ends_with(" 3 | 4 | 5 "));
+    /// assert_eq!(lines[21], "   60| 61| 62  |   69| 70| 71  |   78| 79| 80");
+    /// ```
+    pub fn render_help() -> String {
+        Board::new().render_labels()
+    }
+
+    /// Returns the rules the game is played with
+    pub fn rules(&self) -> RuleSet { self.rules }
+
+    /// Returns the Zobrist hash of the board and next player, with the keys
+    /// of `ZobristHasher::new()`.
+    ///
+    /// The hash is updated as moves are played and undone, rather than
+    /// recomputed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, ZobristHasher, ai::RandomBot};
+    ///
+    /// let hasher = ZobristHasher::new();
+    ///
+    /// for seed in 1..20 {
+    ///     let mut bot = RandomBot::new(seed);
+    ///     let mut game = STTT::new();
+    ///
+    ///     while let Some(position) = bot.choose(&game) {
+    ///         game.play_move(position).unwrap();
+    ///         assert_eq!(game.zobrist(), hasher.hash(&game.board(), game.player()));
+    ///
+    ///         if game.history().len() % 3 == 0 {
+    ///             game.undo().unwrap();
+    ///             assert_eq!(game.zobrist(), hasher.hash(&game.board(), game.player()));
+    ///             let position = bot.choose(&game).unwrap();
+    ///             game.play_move(position).unwrap();
+    ///         }
+    ///     }
+    ///
+    ///     while game.undo().is_ok() {
+    ///         assert_eq!(game.zobrist(), hasher.hash(&game.board(), game.player()));
+    ///     }
+    ///     assert_eq!(game.zobrist(), 0);
+    /// }
+    /// ```
+    pub fn zobrist(&self) -> u64 { self.zobrist }
+
+    /// Sets a function to be called with the board index and the winner
+    /// each time a move wins a small board, replacing any previous one.
     ///
-    /// This function returns an error if a player plays in the other's turn,
-    /// if the given position is out of bounds, or if the play is invalid in the board.
+    /// The callback is not copied when the game is cloned. It must be `Send`,
+    /// so that the game can still be moved to another thread.
     ///
     /// # Examples
     ///
     /// ```
-    /// use sttt::{STTT,Player, Position};
+    /// use std::sync::{Arc, Mutex};
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let won = Arc::new(Mutex::new(Vec::new()));
+    /// let recorder = Arc::clone(&won);
+    ///
+    /// let mut game = STTT::new();
+    /// game.set_on_subboard_won(Box::new(move |board_idx, player| {
+    ///     recorder.lock().unwrap().push((board_idx, player));
+    /// }));
+    ///
+    /// // X wins the center board on its top row
+    /// for &pos in &[36, 4, 37, 13, 38] {
+    ///     game.play_move(Position::from_absolute(pos).unwrap()).unwrap();
+    ///     if pos == 37 {
+    ///         // hypothetical moves on a clone don't fire the callback
+    ///         let mut preview = game.clone();
+    ///         preview.play_move(Position::from_absolute(13).unwrap()).unwrap();
+    ///         preview.play_move(Position::from_absolute(38).unwrap()).unwrap();
+    ///     }
+    /// }
+    /// assert_eq!(*won.lock().unwrap(), vec![(4, Player::X)]);
+    ///
+    /// // other moves don't fire it
+    /// game.play_move(Position::from_absolute(22).unwrap()).unwrap();
+    /// assert_eq!(won.lock().unwrap().len(), 1);
+    /// ```
+    pub fn set_on_subboard_won(&mut self, callback: Box<dyn FnMut(usize, Player) + Send>) {
+        self.on_subboard_won = Some(callback);
+    }
+
+    /// Returns `true` if the given board can still be played in under the
+    /// rules of the game. Unlike `Board::is_open`, this takes
+    /// `RuleSet::play_in_won_boards` into account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::STTT;
+    ///
+    /// // X won board 0
+    /// let game = STTT::from_code(&format!("XXX{}O", ".".repeat(78))).unwrap();
+    /// assert!(!game.is_open(0));
+    /// assert!(game.is_open(1));
+    /// ```
+    pub fn is_open(&self, board_idx: usize) -> bool {
+        self.rules.is_open(&self.board, board_idx)
+    }
+
+    /// Returns the moves played so far, in order.
+    ///
+    /// Rejected moves are not recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
     ///
     /// let p1 = Position::from_absolute(0).unwrap();
     /// let p2 = Position::from_absolute(1).unwrap();
@@ -106,61 +641,1749 @@ impl STTT {
     ///
     /// let mut game = STTT::new();
     /// game.play(Player::X, p1).unwrap();
+    /// assert!(game.play(Player::O, p3).is_err());
     /// game.play(Player::O, p2).unwrap();
     /// game.play(Player::X, p3).unwrap();
+    ///
+    /// assert_eq!(game.history(), &[(Player::X, p1), (Player::O, p2), (Player::X, p3)]);
     /// ```
-    pub fn play(&mut self, player: Player, position: Position) -> Result<Status, &str> {
-        // Step 1: Check if valid play
-        if player != self.player {
-            return Err("It's not your turn!");
-        }
-        if !self.valid_boards.contains(&position.board_idx()) {
-            return Err("You cannot play in that board!");
-        }
+    pub fn history(&self) -> &[(Player, Position)] { &self.history }
 
-        // Step 2: Play the given move
-        if let Err(msg) = self.board.play(self.player, position) {
-            return Err(msg);
-        }
+    /// Returns the positions of the moves played so far, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let pos = Position::from_absolute(40).unwrap();
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, pos).unwrap();
+    /// assert_eq!(game.history_positions(), vec![pos]);
+    /// ```
+    pub fn history_positions(&self) -> Vec<Position> {
+        self.history.iter().map(|&(_, position)| position).collect()
+    }
 
-        // Step 3: Check winner
-        if let Some(winner) = Board::check_winner(&self.board.metaboard()) {
-            assert!(winner == player);
-            return Ok(Status::Winner(winner));
+    /// Returns a storyboard of the game: the boards after `count` roughly
+    /// evenly spaced moves of the history, after every move that captured a
+    /// board, and the current board, in the order they were played.
+    ///
+    /// Games without history only have the current board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, ai::RandomBot};
+    ///
+    /// let mut bot = RandomBot::new(1);
+    /// let mut game = STTT::new();
+    /// while let Some(position) = bot.choose(&game) {
+    ///     game.play_move(position).unwrap();
+    /// }
+    ///
+    /// let keys = game.key_positions(4);
+    /// assert!(*keys.last().unwrap() == game.board());
+    ///
+    /// let moves = game.history_positions();
+    /// let mut captures = 0;
+    /// for board_idx in 0..9 {
+    ///     if let Some((_, n)) = game.capturing_move(board_idx) {
+    ///         let after = STTT::from_moves(&moves[..n]).unwrap().board();
+    ///         assert!(keys.contains(&after));
+    ///         captures += 1;
+    ///     }
+    /// }
+    /// assert!(captures >= 3);
+    /// assert!(keys.len() <= 4 + captures);
+    ///
+    /// assert!(STTT::new().key_positions(4) == vec![STTT::new().board()]);
+    /// ```
+    pub fn key_positions(&self, count: usize) -> Vec<Board> {
+        let records = self.move_records();
+        if records.is_empty() {
+            return vec![self.board];
         }
 
-        // Step 4: Prepare next move
-        self.valid_boards.clear();
-        let next_board = position.tile_idx();
-        if self.board.is_open(next_board) {
-            // Play in corresponding board if open
-            self.valid_boards.insert(next_board);
-        } else {
-            // Otherwise play in every available board
-            for board in 0..9 {
-                if self.board.is_open(board) {
-                    self.valid_boards.insert(board);
-                }
-            }
-        }
+        let moves = records.len();
+        let mut keys: Vec<usize> = (1..=count).map(|k| k * moves / count).collect();
+        keys.extend(records.iter().enumerate()
+            .filter(|(_, record)| record.captured)
+            .map(|(n, _)| n + 1));
+        keys.push(moves);
 
-        println!("Valid boards: {:?}", self.valid_boards);
+        keys.sort_unstable();
+        keys.dedup();
+        keys.into_iter()
+            .filter(|&n| n > 0)
+            .map(|n| records[n - 1].board)
+            .collect()
+    }
 
-        if self.valid_boards.is_empty() {
-            return Ok(Status::Tie);
-        }
-        
-        self.player = self.next_player();
-        
-        Ok(Status::InProgress)
+    /// Returns the last move played and who played it, or `None` if no move
+    /// was played yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let p1 = Position::from_absolute(40).unwrap();
+    /// let p2 = Position::from_absolute(36).unwrap();
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.last_move(), None);
+    ///
+    /// game.play(Player::X, p1).unwrap();
+    /// assert_eq!(game.last_move(), Some((Player::X, p1)));
+    /// game.play(Player::O, p2).unwrap();
+    /// assert_eq!(game.last_move(), Some((Player::O, p2)));
+    ///
+    /// game.undo().unwrap();
+    /// assert_eq!(game.last_move(), Some((Player::X, p1)));
+    /// game.undo().unwrap();
+    /// assert_eq!(game.last_move(), None);
+    /// ```
+    pub fn last_move(&self) -> Option<(Player, Position)> {
+        self.history.last().cloned()
     }
 
+    /// Returns the current `Status` of the game, without making a move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Status};
+    ///
+    /// assert_eq!(STTT::new().status(), Status::InProgress);
+    ///
+    /// // every board was won, but there is no line in the metaboard
+    /// let owners = "XOXXOOOXX";
+    /// let code: String = owners.chars()
+    ///     .map(|owner| format!("{}{}{}......", owner, owner, owner))
+    ///     .collect();
+    /// let game = STTT::from_code(&format!("{}X", code)).unwrap();
+    /// assert_eq!(game.status(), Status::Tie);
+    /// ```
+    pub fn status(&self) -> Status {
+        if let Some(winner) = self.board.winner() {
+            Status::Winner(winner)
+        } else if !(0..9).any(|board_idx| self.is_open(board_idx)) {
+            Status::Tie
+        } else {
+            Status::InProgress
+        }
+    }
 
-    fn next_player(&self) -> Player {
-        match self.player {
-            Player::X => Player::O,
-            Player::O => Player::X,
+    /// Returns the winner of the game, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player};
+    ///
+    /// // X won the top row of the metaboard
+    /// let won = STTT::from_code(&format!("{}{}X", "XXX......".repeat(3), ".".repeat(54))).unwrap();
+    /// assert_eq!(won.winner(), Some(Player::X));
+    /// assert!(won.is_over());
+    ///
+    /// assert_eq!(STTT::new().winner(), None);
+    /// assert!(!STTT::new().is_over());
+    /// ```
+    pub fn winner(&self) -> Option<Player> {
+        match self.status() {
+            Status::Winner(player) => Some(player),
+            _ => None,
         }
     }
-}
 
+    /// Returns the player who won the given small board, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `board_idx` is greater or equal than `9`, like `Board::get`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player};
+    ///
+    /// // X won board 0
+    /// let game = STTT::from_code(&format!("XXX{}O", ".".repeat(78))).unwrap();
+    /// assert_eq!(game.sub_board_winner(0), Some(Player::X));
+    /// assert_eq!(game.sub_board_winner(1), None);
+    /// ```
+    pub fn sub_board_winner(&self, board_idx: usize) -> Option<Player> {
+        assert!(board_idx < 9);
+
+        self.board.metaboard()[board_idx]
+    }
+
+    /// Returns the move that won the given small board and its number,
+    /// counting from `1`, or `None` if the board is undecided, drawn, or was
+    /// won before the history of the game starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `board_idx` is greater or equal than `9`, like `Board::get`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let moves: Vec<Position> = [40, 36, 4, 37, 13, 38].iter()
+    ///     .map(|&i| Position::from_absolute(i).unwrap())
+    ///     .collect();
+    /// let game = STTT::from_moves(&moves).unwrap();
+    ///
+    /// // O completes the top row of board 4 with the sixth move
+    /// assert_eq!(game.capturing_move(4), Some((Position::from_absolute(38).unwrap(), 6)));
+    /// assert_eq!(game.capturing_move(0), None);
+    /// ```
+    pub fn capturing_move(&self, board_idx: usize) -> Option<(Position, usize)> {
+        assert!(board_idx < 9);
+
+        self.move_records().iter()
+            .enumerate()
+            .find(|&(_, record)| record.captured && record.position.board_idx() == board_idx)
+            .map(|(n, record)| (record.position, n + 1))
+    }
+
+    /// Returns the boards of the metaboard line that won the game, in
+    /// increasing order, or `None` if nobody won.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::STTT;
+    ///
+    /// assert_eq!(STTT::new().winning_line(), None);
+    ///
+    /// let won = STTT::from_code(&format!("{}{}X", "XXX......".repeat(3), ".".repeat(54))).unwrap();
+    /// assert_eq!(won.winning_line(), Some([0, 1, 2]));
+    /// ```
+    pub fn winning_line(&self) -> Option<[usize; 3]> {
+        Board::winning_line(&self.board.metaboard()).map(|(_, line)| line)
+    }
+
+    /// Returns `true` if the game was won or tied.
+    ///
+    /// See `STTT::winner` for examples.
+    pub fn is_over(&self) -> bool {
+        self.status() != Status::InProgress
+    }
+
+    /// Returns the indices of the boards the next player can play in, in
+    /// increasing order.
+    ///
+    /// This reflects the state of the game after the last successful `play`,
+    /// so there are none once the game is over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.valid_boards(), (0..9).collect::<Vec<_>>());
+    ///
+    /// // X sends O to board 4
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    /// assert_eq!(game.valid_boards(), vec![4]);
+    /// ```
+    ///
+    /// With `SendToClosed::OpponentChooses`, there are no valid boards while
+    /// `STTT::pending_choice` waits for the opponent's `STTT::choose_board`.
+    pub fn valid_boards(&self) -> Vec<usize> {
+        let mut res: Vec<usize> = self.valid_boards.iter().cloned().collect();
+        res.sort_unstable();
+        res
+    }
+
+    /// Returns a copy of the state of the game.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, Status};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play_move(Position::from_absolute(40).unwrap()).unwrap();
+    ///
+    /// let snapshot = game.snapshot();
+    /// assert_eq!(snapshot.cells.len(), 81);
+    /// assert_eq!(snapshot.cells[40], Some(Player::X));
+    /// assert_eq!(snapshot.player, Player::O);
+    /// assert_eq!(snapshot.status, Status::InProgress);
+    /// assert!(snapshot.to_json().contains(r#""valid_boards":[4]"#));
+    /// ```
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            cells: self.board.cells().map(|(_, tile)| tile).collect(),
+            metaboard: self.board.metaboard(),
+            player: self.player,
+            valid_boards: if self.is_over() { Vec::new() } else { self.valid_boards() },
+            status: self.status(),
+        }
+    }
+
+    /// Returns the only board the next player can play in, or `None` if
+    /// they can choose between several boards or the game is over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.forced_board(), None);
+    ///
+    /// game.play_move(Position::from_absolute(40).unwrap()).unwrap();
+    /// assert_eq!(game.forced_board(), Some(4));
+    /// ```
+    pub fn forced_board(&self) -> Option<usize> {
+        if self.valid_boards.len() != 1 || self.is_over() {
+            return None;
+        }
+        self.valid_boards.iter().next().cloned()
+    }
+
+    /// Returns every position the next player can play in, ordered by
+    /// their absolute index.
+    ///
+    /// The result is empty if and only if the game is over or the next
+    /// player waits for a board to be chosen (see `STTT::pending_choice`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.valid_moves().len(), 81);
+    ///
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    /// assert_eq!(game.valid_moves().len(), 8);
+    ///
+    /// game.play(Player::O, Position::from_absolute(36).unwrap()).unwrap();
+    /// assert_eq!(game.valid_moves().len(), 9);
+    /// assert_eq!(game.valid_moves()[0], Position::from_absolute(0).unwrap());
+    /// ```
+    pub fn valid_moves(&self) -> Vec<Position> {
+        if self.board.winner().is_some() {
+            return Vec::new();
+        }
+
+        self.cell_legality().iter()
+            .enumerate()
+            .filter(|&(_, &legality)| legality == CellLegality::Legal)
+            .map(|(pos, _)| Position::from_absolute(pos).unwrap())
+            .collect()
+    }
+
+    /// Returns how many moves each player has played, as `(X, O)`.
+    ///
+    /// In a legal game these never differ by more than one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, Position::from_absolute(0).unwrap()).unwrap();
+    /// assert_eq!(game.moves_played(), (1, 0));
+    /// game.play(Player::O, Position::from_absolute(1).unwrap()).unwrap();
+    /// assert_eq!(game.moves_played(), (1, 1));
+    /// game.play(Player::X, Position::from_absolute(9).unwrap()).unwrap();
+    /// assert_eq!(game.moves_played(), (2, 1));
+    /// ```
+    pub fn moves_played(&self) -> (usize, usize) {
+        (self.board.count(Player::X), self.board.count(Player::O))
+    }
+
+    /// Returns the number of moves played so far.
+    ///
+    /// This counts the tiles on the board, so it also covers games rebuilt
+    /// with `STTT::decode` or `STTT::from_code`, which have no history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.move_count(), 0);
+    ///
+    /// game.play_move(Position::from_absolute(40).unwrap()).unwrap();
+    /// assert_eq!(game.move_count(), 1);
+    /// assert_eq!(STTT::from_code(&game.to_code()).unwrap().move_count(), 1);
+    /// ```
+    pub fn move_count(&self) -> usize {
+        let (x_count, o_count) = self.moves_played();
+        x_count + o_count
+    }
+
+    /// Packs the whole game state in 23 bytes, to be used as a compact key.
+    ///
+    /// The bits are laid out as follows (bit `i` is bit `i % 8` of byte `i / 8`):
+    ///  * bits `2n` and `2n + 1` hold the tile with absolute index `n`:
+    ///    `0` if empty, `1` if played by `X` and `2` if played by `O`;
+    ///  * bit 162 holds the next player: `0` for `X` and `1` for `O`;
+    ///  * bits 163 to 171 hold the valid boards, one bit per board;
+    ///  * bits 172 to 180 hold the owner of each board where both players
+    ///    have a line, `0` for `X` and `1` for `O`, and are `0` for the others;
+    ///  * bit 181 is set with `RuleSet::play_in_won_boards`, bit 182 with
+    ///    `SendToClosed::OpponentChooses` and bit 183 with `RuleSet::misere`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    ///
+    /// let code = game.encode();
+    /// let decoded = STTT::decode(&code).unwrap();
+    /// assert_eq!(decoded.player(), Player::O);
+    /// assert_eq!(decoded.encode(), code);
+    /// ```
+    pub fn encode(&self) -> [u8; 23] {
+        let mut res = [0; 23];
+        let mut set_bits = |bit: usize, value: u8| res[bit / 8] |= value << (bit % 8);
+
+        for pos in 0..81 {
+            match self.board.get(pos / 9, pos % 9) {
+                None => {},
+                Some(Player::X) => set_bits(2 * pos, 1),
+                Some(Player::O) => set_bits(2 * pos + 1, 1),
+            }
+        }
+
+        if self.player == Player::O {
+            set_bits(162, 1);
+        }
+
+        for &board_idx in self.valid_boards.iter() {
+            set_bits(163 + board_idx, 1);
+        }
+
+        for board_idx in 0..9 {
+            if self.board.is_contested(board_idx) && self.board.metaboard()[board_idx] == Some(Player::O) {
+                set_bits(172 + board_idx, 1);
+            }
+        }
+
+        if self.rules.play_in_won_boards {
+            set_bits(181, 1);
+        }
+        if self.rules.send_to_closed == SendToClosed::OpponentChooses {
+            set_bits(182, 1);
+        }
+        if self.rules.misere {
+            set_bits(183, 1);
+        }
+
+        res
+    }
+
+    /// Rebuilds a game from the output of `STTT::encode`.
+    ///
+    /// The encoding doesn't include the moves that were played, so the
+    /// history of the rebuilt game is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a tile holds an invalid value or an owner is given
+    /// for a board where only one player has a line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, Status};
+    ///
+    /// // play a pseudo-random game, checking the encoding at every step
+    /// let mut game = STTT::new();
+    /// let mut seed: usize = 7;
+    /// for _ in 0..60 {
+    ///     let status = loop {
+    ///         seed = (seed * 1103515245 + 12345) % (1 << 31);
+    ///         let pos = Position::from_absolute(seed % 81).unwrap();
+    ///         if let Ok(status) = game.play(game.player(), pos) {
+    ///             break status;
+    ///         }
+    ///     };
+    ///
+    ///     let code = game.encode();
+    ///     let decoded = STTT::decode(&code).unwrap();
+    ///     assert_eq!(decoded.encode(), code);
+    ///     assert_eq!(decoded.player(), game.player());
+    ///     assert_eq!(decoded.board().to_string(), game.board().to_string());
+    ///
+    ///     if let Status::InProgress = status {} else { break; }
+    /// }
+    ///
+    /// assert!(STTT::decode(&[0xff; 23]).is_err());
+    /// ```
+    pub fn decode(code: &[u8; 23]) -> Result<STTT, &'static str> {
+        let get_bits = |bit: usize, len: usize| (code[bit / 8] >> (bit % 8)) & ((1 << len) - 1);
+
+        let mut cells = Vec::new();
+        for pos in 0..81 {
+            let player = match get_bits(2 * pos, 2) {
+                0 => continue,
+                1 => Player::X,
+                2 => Player::O,
+                _ => return Err("Invalid tile value"),
+            };
+            cells.push((Position::from_absolute(pos)?, player));
+        }
+
+        let player = if get_bits(162, 1) == 0 { Player::X } else { Player::O };
+
+        let valid_boards = (0..9)
+            .filter(|board_idx| get_bits(163 + board_idx, 1) == 1)
+            .collect();
+
+        let rules = RuleSet {
+            play_in_won_boards: get_bits(181, 1) == 1,
+            send_to_closed: if get_bits(182, 1) == 0 { SendToClosed::FreeChoice } else { SendToClosed::OpponentChooses },
+            misere: get_bits(183, 1) == 1,
+        };
+
+        let mut board = Board::from_cells(&cells)?;
+        if rules.misere {
+            STTT::hand_over_boards(&mut board);
+        }
+        for board_idx in 0..9 {
+            match (board.is_contested(board_idx), get_bits(172 + board_idx, 1)) {
+                (true, 0) => board.set_owner(board_idx, Player::X),
+                (true, _) => board.set_owner(board_idx, Player::O),
+                (false, 0) => {},
+                (false, _) => return Err("Owner given for an uncontested board"),
+            }
+        }
+
+        Ok(STTT {
+            player,
+            board,
+            valid_boards,
+            history: Vec::new(),
+            rules,
+            zobrist: zobrist::DEFAULT.hash(&board, player),
+            on_subboard_won: None,
+        })
+    }
+
+    /// Returns the `CellLegality` of every tile, indexed by absolute position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, CellLegality};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    ///
+    /// let legality = game.cell_legality();
+    /// assert_eq!(legality[40], CellLegality::Occupied);
+    /// assert_eq!(legality[36], CellLegality::Legal);
+    /// assert_eq!(legality[0], CellLegality::WrongBoard);
+    /// assert_eq!(legality.iter().filter(|&&l| l == CellLegality::Legal).count(), 8);
+    /// ```
+    pub fn cell_legality(&self) -> [CellLegality; 81] {
+        let mut res = [CellLegality::Legal; 81];
+
+        for (pos, legality) in res.iter_mut().enumerate() {
+            let (board_idx, tile_idx) = (pos / 9, pos % 9);
+            *legality = if self.board.get(board_idx, tile_idx).is_some() {
+                CellLegality::Occupied
+            } else if !self.is_open(board_idx) {
+                CellLegality::BoardDecided
+            } else if !self.valid_boards.contains(&board_idx) {
+                CellLegality::WrongBoard
+            } else {
+                CellLegality::Legal
+            };
+        }
+
+        res
+    }
+
+    /// Scores each tile by how much playing it improves the position of the
+    /// next player, as the change in `ai::evaluate` one move ahead, indexed by
+    /// absolute position.
+    ///
+    /// Tiles that can't be played score `0`, and legal moves that worsen the
+    /// position, e.g. by closing the last open board of a threatened line,
+    /// score below `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::STTT;
+    ///
+    /// // X owns boards 0 and 1, and takes board 2, and the game, at 20
+    /// let game = STTT::from_code(&format!("XXX......XXX......XX{}X", ".".repeat(61))).unwrap();
+    /// let importance = game.cell_importance();
+    /// let best = (0..81).max_by(|&a, &b| importance[a].partial_cmp(&importance[b]).unwrap());
+    /// assert_eq!(best, Some(20));
+    /// assert_eq!(importance[18], 0.0);
+    /// ```
+    pub fn cell_importance(&self) -> [f32; 81] {
+        let mut res = [0.0; 81];
+
+        let sign = if self.player == Player::X { 1 } else { -1 };
+        let before = ai::evaluate(self);
+
+        for position in self.valid_moves() {
+            let mut after = self.clone();
+            after.play_move(position).unwrap();
+            let delta = sign * (ai::evaluate(&after) - before);
+            res[position.to_absolute()] = delta as f32;
+        }
+
+        res
+    }
+
+    /// Returns `true` if every legal move loses the game against the best
+    /// defence, searching `depth` moves ahead like `ai::best_move`, i.e. the
+    /// position is lost whatever the next player does.
+    ///
+    /// Returns `false` if there are no legal moves (see `STTT::valid_moves`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::STTT;
+    ///
+    /// assert!(!STTT::new().all_moves_losing(2));
+    /// ```
+    pub fn all_moves_losing(&self, depth: usize) -> bool {
+        let moves = self.valid_moves();
+
+        !moves.is_empty() && moves.into_iter().all(|position| {
+            let mut after = self.clone();
+            after.play_move(position).unwrap();
+            ai::forces_loss(&after, self.player, depth.max(1) - 1)
+        })
+    }
+
+    /// Returns the board the next player would be forced to play in after
+    /// playing at `position` and the opponent replying with `ai::best_move`,
+    /// searching `depth` moves ahead, or `None` if they could choose between
+    /// several boards or the game would be over.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `PlayError` of playing at `position` in this game.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// // O has the first two tiles of the top row of board 4
+    /// let game = STTT::from_code(&format!("X{}OO......X{}X", ".".repeat(35), ".".repeat(36))).unwrap();
+    ///
+    /// // X sends O to board 4, where O wins the board at 4:2 and sends X to board 2
+    /// let position = Position::from_relative(0, 4).unwrap();
+    /// assert_eq!(game.constraint_after_best_reply(position, 2), Ok(Some(2)));
+    /// ```
+    pub fn constraint_after_best_reply(&self, position: Position, depth: usize) -> Result<Option<usize>, PlayError> {
+        let mut after = self.clone();
+        after.play_move(position)?;
+
+        match ai::best_move(&after, depth) {
+            Some(reply) => {
+                after.play_move(reply).unwrap();
+                Ok(after.forced_board())
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Returns every legal move for the next player, paired with the board
+    /// that results from playing it.
+    ///
+    /// Moves are ordered by their absolute position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, CellLegality};
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.successor_boards().len(), 81);
+    ///
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    /// let legal = game.cell_legality().iter()
+    ///     .filter(|&&l| l == CellLegality::Legal)
+    ///     .count();
+    /// assert_eq!(game.successor_boards().len(), legal);
+    /// ```
+    pub fn successor_boards(&self) -> Vec<(Position, Board)> {
+        self.valid_moves().into_iter()
+            .map(|position| {
+                let mut board = self.board;
+                self.rules.play(&mut board, self.player, position).unwrap();
+                (position, board)
+            })
+            .collect()
+    }
+
+    /// Checks necessary conditions for the given board to be reachable in a
+    /// game started by `X`:
+    ///  * `X` played as many tiles as `O`, or one more;
+    ///  * the metaboard matches the winners of the small boards: each won
+    ///    board has a line of its winner, and the others have no line.
+    ///  * at most one player won the game, having played the last move.
+    ///
+    /// A board passing these checks may still be unreachable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Board, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    /// game.play(Player::O, Position::from_absolute(41).unwrap()).unwrap();
+    /// assert!(STTT::is_reachable(&game.board()));
+    ///
+    /// let cells: Vec<(Position, Player)> = [0, 1, 2].iter()
+    ///     .map(|&i| (Position::from_absolute(i).unwrap(), Player::X))
+    ///     .collect();
+    /// assert!(!STTT::is_reachable(&Board::from_cells(&cells).unwrap()));
+    /// ```
+    pub fn is_reachable(board: &Board) -> bool {
+        let (x_count, o_count) = (board.count(Player::X), board.count(Player::O));
+        if x_count != o_count && x_count != o_count + 1 {
+            return false;
+        }
+
+        let metaboard = board.metaboard();
+        if !(0..9).all(|board_idx| STTT::owner_matches(board, board_idx, false)) {
+            return false;
+        }
+
+        match (STTT::has_line(&metaboard, Player::X), STTT::has_line(&metaboard, Player::O)) {
+            (true, true) => false,
+            (true, false) => x_count == o_count + 1,
+            (false, true) => x_count == o_count,
+            (false, false) => true,
+        }
+    }
+
+    /// Encodes the game in a short, human readable string.
+    ///
+    /// The code has the following grammar:
+    /// ```text
+    /// code  := tile{81} turn board? owner* rule*
+    /// tile  := '.' | 'X' | 'O'
+    /// turn  := 'X' | 'O'
+    /// board := '0' | '1' | ... | '8' | '-'
+    /// owner := 'X' | 'O'
+    /// rule  := 'w' | 'c' | 'm'
+    /// ```
+    /// The tiles are in absolute order (see `Position::from_absolute`),
+    /// `turn` is the next player and `board` is present only when the next
+    /// player is forced to play in that board, or `-` while a board choice
+    /// is pending (see `STTT::pending_choice`). Then come the owners of the
+    /// boards where both players have a line, in board order, and `w` with
+    /// `RuleSet::play_in_won_boards`, `c` with `SendToClosed::OpponentChooses`
+    /// and `m` with `RuleSet::misere`, in that order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, Position::from_absolute(5).unwrap()).unwrap();
+    ///
+    /// let code = game.to_code();
+    /// assert_eq!(code, format!(".....X{}O5", ".".repeat(75)));
+    /// ```
+    pub fn to_code(&self) -> String {
+        let mut res = String::with_capacity(83);
+
+        for pos in 0..81 {
+            match self.board.get(pos / 9, pos % 9) {
+                None => res.push('.'),
+                Some(p) => res.push_str(&p.to_string()),
+            }
+        }
+
+        res.push_str(&self.player.to_string());
+
+        let valid_boards = self.valid_boards();
+        let open = (0..9).filter(|&board_idx| self.is_open(board_idx)).count();
+        if valid_boards.len() == 1 && open > 1 {
+            res.push_str(&valid_boards[0].to_string());
+        } else if self.pending_choice().is_some() {
+            res.push('-');
+        }
+
+        for board_idx in (0..9).filter(|&board_idx| self.board.is_contested(board_idx)) {
+            res.push_str(&self.board.metaboard()[board_idx].unwrap().to_string());
+        }
+
+        if self.rules.play_in_won_boards {
+            res.push('w');
+        }
+        if self.rules.send_to_closed == SendToClosed::OpponentChooses {
+            res.push('c');
+        }
+        if self.rules.misere {
+            res.push('m');
+        }
+
+        res
+    }
+
+    /// Parses a game from the format produced by `STTT::to_code`.
+    ///
+    /// Without a forced board, every open board is valid. The history of the
+    /// parsed game is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the problem if the code is malformed, the
+    /// forced board is not open, or the owners don't match the boards where
+    /// both players have a line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let moves: Vec<Position> = [40, 36, 4].iter()
+    ///     .map(|&i| Position::from_absolute(i).unwrap())
+    ///     .collect();
+    /// let game = STTT::from_moves(&moves).unwrap();
+    ///
+    /// let parsed = STTT::from_code(&game.to_code()).unwrap();
+    /// assert_eq!(parsed.encode(), game.encode());
+    ///
+    /// assert!(STTT::from_code("").is_err());
+    /// assert!(STTT::from_code(&format!("{}X", "-".repeat(81))).is_err());
+    /// assert!(STTT::from_code(&format!("{}Z", ".".repeat(81))).is_err());
+    /// assert!(STTT::from_code(&format!("{}X9", ".".repeat(81))).is_err());
+    /// ```
+    pub fn from_code(code: &str) -> Result<STTT, String> {
+        let chars: Vec<char> = code.chars().collect();
+        if chars.len() < 82 {
+            return Err(format!("Expected at least 82 characters, found {}", chars.len()));
+        }
+
+        let mut cells = Vec::new();
+        for (pos, &c) in chars[..81].iter().enumerate() {
+            let player = match c {
+                '.' => continue,
+                'X' => Player::X,
+                'O' => Player::O,
+                _ => return Err(format!("Invalid tile '{}' at position {}", c, pos)),
+            };
+            cells.push((Position::from_absolute(pos)?, player));
+        }
+
+        let player = match chars[81] {
+            'X' => Player::X,
+            'O' => Player::O,
+            c => return Err(format!("Invalid player '{}'", c)),
+        };
+
+        let mut rest = chars[82..].iter().peekable();
+        let forced = match rest.peek() {
+            Some(&&c) if c == '-' || c.is_ascii_digit() => {
+                rest.next();
+                match c.to_digit(10) {
+                    Some(d) if d < 9 => Some(Some(d as usize)),
+                    Some(_) => return Err(format!("Invalid board '{}'", c)),
+                    None => Some(None),
+                }
+            },
+            _ => None,
+        };
+
+        let mut board = Board::from_cells(&cells)?;
+        for board_idx in 0..9 {
+            if !board.is_contested(board_idx) {
+                continue;
+            }
+            match rest.next() {
+                Some('X') => board.set_owner(board_idx, Player::X),
+                Some('O') => board.set_owner(board_idx, Player::O),
+                _ => return Err(format!("Expected the owner of board {}", board_idx)),
+            }
+        }
+
+        let mut rules = RuleSet::default();
+        if rest.peek() == Some(&&'w') {
+            rest.next();
+            rules.play_in_won_boards = true;
+        }
+        if rest.peek() == Some(&&'c') {
+            rest.next();
+            rules.send_to_closed = SendToClosed::OpponentChooses;
+        }
+        if rest.peek() == Some(&&'m') {
+            rest.next();
+            rules.misere = true;
+            STTT::hand_over_boards(&mut board);
+        }
+        if let Some(c) = rest.next() {
+            return Err(format!("Unexpected '{}' after the rules", c));
+        }
+
+        let mut game = STTT {
+            player,
+            board,
+            valid_boards: HashSet::new(),
+            history: Vec::new(),
+            rules,
+            zobrist: zobrist::DEFAULT.hash(&board, player),
+            on_subboard_won: None,
+        };
+
+        match forced {
+            Some(Some(board_idx)) if !game.is_open(board_idx) => {
+                return Err(format!("Board {} is not open", board_idx));
+            },
+            Some(Some(board_idx)) => {
+                game.valid_boards.insert(board_idx);
+            },
+            Some(None) if rules.send_to_closed != SendToClosed::OpponentChooses => {
+                return Err("Only the opponent can choose a board".to_string());
+            },
+            Some(None) => {},
+            None => game.update_valid_boards(None),
+        }
+
+        Ok(game)
+    }
+
+    /// Sets up a game in the given position, with `next_player` to play and
+    /// `last_move` being the move that led to it, if any.
+    ///
+    /// The valid boards follow from `last_move` as after `STTT::play`, and
+    /// every open board is valid without it. The history of the game is
+    /// empty and it uses the default rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GameOver` if the board was already won, and `InvalidPosition`
+    /// if `last_move` is not a tile of the opponent of `next_player`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{Board, STTT, Player, Position, PlayError};
+    ///
+    /// let pos = |i| Position::from_absolute(i).unwrap();
+    ///
+    /// let mut board = Board::new();
+    /// board.play(Player::X, pos(40)).unwrap();
+    /// board.play(Player::O, pos(38)).unwrap();
+    /// board.play(Player::X, pos(22)).unwrap();
+    ///
+    /// // O must play in board 4, where two tiles are taken
+    /// let game = STTT::set_position(board, Player::O, Some(pos(22))).unwrap();
+    /// assert_eq!(game.valid_boards(), vec![4]);
+    /// let expected: Vec<Position> = [36, 37, 39, 41, 42, 43, 44].iter().map(|&i| pos(i)).collect();
+    /// assert_eq!(game.valid_moves(), expected);
+    ///
+    /// let free = STTT::set_position(board, Player::O, None).unwrap();
+    /// assert_eq!(free.valid_moves().len(), 78);
+    ///
+    /// assert_eq!(STTT::set_position(board, Player::X, Some(pos(22))).err(), Some(PlayError::InvalidPosition));
+    /// assert_eq!(STTT::set_position(board, Player::O, Some(pos(0))).err(), Some(PlayError::InvalidPosition));
+    ///
+    /// let cells: Vec<(Position, Player)> = [0, 1, 2, 9, 10, 11, 18, 19, 20].iter()
+    ///     .map(|&i| (pos(i), Player::X))
+    ///     .collect();
+    /// let won = Board::from_cells(&cells).unwrap();
+    /// assert_eq!(STTT::set_position(won, Player::O, Some(pos(20))).err(), Some(PlayError::GameOver));
+    /// ```
+    pub fn set_position(board: Board, next_player: Player, last_move: Option<Position>) -> Result<STTT, PlayError> {
+        if board.winner().is_some() {
+            return Err(PlayError::GameOver);
+        }
+        if let Some(position) = last_move {
+            match board.get_by_position(position) {
+                Some(player) if player == next_player.opponent() => {},
+                _ => return Err(PlayError::InvalidPosition),
+            }
+        }
+
+        let mut game = STTT {
+            player: next_player,
+            board,
+            valid_boards: HashSet::new(),
+            history: Vec::new(),
+            rules: RuleSet::default(),
+            zobrist: zobrist::DEFAULT.hash(&board, next_player),
+            on_subboard_won: None,
+        };
+        game.update_valid_boards(last_move);
+
+        Ok(game)
+    }
+
+    /// Writes the moves played so far as a readable transcript, numbering
+    /// each pair of moves as in chess notation, e.g. `1. X 4:4 O 4:0`, with
+    /// positions in the `Position` display format.
+    ///
+    /// Each numbered line ends with a newline. Games rebuilt with
+    /// `STTT::decode`, `STTT::from_code` or `STTT::set_position` have no
+    /// history, so their transcript is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let moves: Vec<Position> = [40, 36, 0].iter()
+    ///     .map(|&i| Position::from_absolute(i).unwrap())
+    ///     .collect();
+    /// let game = STTT::from_moves(&moves).unwrap();
+    ///
+    /// assert_eq!(game.to_transcript(), "1. X 4:4 O 4:0\n2. X 0:0\n");
+    /// assert_eq!(STTT::new().to_transcript(), "");
+    /// ```
+    pub fn to_transcript(&self) -> String {
+        let mut res = String::new();
+
+        for (n, pair) in self.history.chunks(2).enumerate() {
+            res.push_str(&format!("{}.", n + 1));
+            for &(player, position) in pair {
+                res.push_str(&format!(" {} {}", player, position));
+            }
+            res.push('\n');
+        }
+
+        res
+    }
+
+    /// Writes the moves played so far for post-game review, one numbered
+    /// move per line, e.g. `6. O 0:6 forced, captures board 0`.
+    ///
+    /// Each move is `forced` if the player could only play in its board, and
+    /// `free` otherwise, and notes the board it won, if any. Like
+    /// `STTT::to_transcript`, this is empty for games without history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Position};
+    ///
+    /// let moves: Vec<Position> = [40, 36, 4, 37, 13, 38].iter()
+    ///     .map(|&i| Position::from_absolute(i).unwrap())
+    ///     .collect();
+    /// let game = STTT::from_moves(&moves).unwrap();
+    ///
+    /// let transcript = game.transcript();
+    /// let lines: Vec<&str> = transcript.lines().collect();
+    /// assert_eq!(lines[0], "1. X 4:4 free");
+    /// assert_eq!(lines[1], "2. O 4:0 forced");
+    /// assert_eq!(lines[5], "6. O 4:2 forced, captures board 4");
+    ///
+    /// assert_eq!(STTT::new().transcript(), "");
+    /// ```
+    pub fn transcript(&self) -> String {
+        let mut res = String::new();
+
+        for (n, record) in self.move_records().iter().enumerate() {
+            let kind = if record.forced { "forced" } else { "free" };
+            res.push_str(&format!("{}. {} {} {}", n + 1, record.player, record.position, kind));
+            if record.captured {
+                res.push_str(&format!(", captures board {}", record.position.board_idx()));
+            }
+            res.push('\n');
+        }
+
+        res
+    }
+
+    /// Replays a game from the output of `STTT::to_transcript`.
+    ///
+    /// Blank lines and surrounding whitespace are ignored. The player of the
+    /// first move starts the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the problem if a line is malformed, the
+    /// moves are not numbered in order, or a move can't be played.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, ai::RandomBot};
+    ///
+    /// for seed in 1..10 {
+    ///     let mut bot = RandomBot::new(seed);
+    ///     let mut game = STTT::new();
+    ///     while let Some(position) = bot.choose(&game) {
+    ///         game.play_move(position).unwrap();
+    ///     }
+    ///
+    ///     let parsed = STTT::from_transcript(&game.to_transcript()).unwrap();
+    ///     assert_eq!(parsed.history(), game.history());
+    ///     assert!(parsed.board() == game.board());
+    ///     assert_eq!(parsed.status(), game.status());
+    /// }
+    ///
+    /// let game = STTT::from_transcript("1. O 4:4 X 4:0\n\n 2. O 0:4 \n").unwrap();
+    /// assert_eq!(game.player(), Player::X);
+    /// assert_eq!(game.history().len(), 3);
+    /// assert_eq!(STTT::from_transcript("").unwrap().history(), &[]);
+    ///
+    /// assert!(STTT::from_transcript("2. X 4:4").is_err());
+    /// assert!(STTT::from_transcript("1. X 4:4 X 4:0").is_err());
+    /// assert!(STTT::from_transcript("1. X 4:4 O 0:0").is_err());
+    /// assert!(STTT::from_transcript("1. X 4:4 O").is_err());
+    /// assert!(STTT::from_transcript("1. Z 4:4").is_err());
+    /// ```
+    pub fn from_transcript(transcript: &str) -> Result<STTT, String> {
+        let mut game: Option<STTT> = None;
+        let lines = transcript.lines().map(str::trim).filter(|line| !line.is_empty());
+
+        for (expected_n, line) in (1..).zip(lines) {
+            let mut tokens = line.split_whitespace();
+
+            let number = tokens.next().unwrap_or("");
+            if number != format!("{}.", expected_n) {
+                return Err(format!("Expected move number {}, found '{}'", expected_n, number));
+            }
+
+            let moves: Vec<&str> = tokens.collect();
+            if moves.len() != 2 && moves.len() != 4 {
+                return Err(format!("Expected one or two moves in '{}'", line));
+            }
+
+            for half in moves.chunks(2) {
+                let player = match half[0] {
+                    "X" => Player::X,
+                    "O" => Player::O,
+                    p => return Err(format!("Invalid player '{}'", p)),
+                };
+                let position: Position = half[1].parse()
+                    .map_err(|e| format!("Invalid position '{}': {}", half[1], e))?;
+
+                let game = game.get_or_insert_with(|| STTT::new_with_starting_player(player));
+                game.play(player, position)
+                    .map_err(|e| format!("Can't play {} {}: {}", player, position, e))?;
+            }
+        }
+
+        Ok(game.unwrap_or_default())
+    }
+
+    /// Returns every inconsistency found in the game state, such as the
+    /// ones produced by decoding corrupted data.
+    ///
+    /// A game only ever changed through `play` has no problems.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, RuleSet, StateProblem, ai::RandomBot};
+    ///
+    /// assert_eq!(STTT::new().diagnose(), vec![]);
+    ///
+    /// // including finished games
+    /// let mut bot = RandomBot::new(1);
+    /// let mut game = STTT::new();
+    /// while let Some(position) = bot.choose(&game) {
+    ///     game.play_move(position).unwrap();
+    ///     assert_eq!(game.diagnose(), vec![]);
+    /// }
+    /// assert!(game.is_over());
+    ///
+    /// // and games where both players complete a line in the same board
+    /// let rules = RuleSet { play_in_won_boards: true, ..RuleSet::default() };
+    /// for seed in 1..40 {
+    ///     let mut bot = RandomBot::new(seed);
+    ///     let mut game = STTT::with_rules(rules);
+    ///     while let Some(position) = bot.choose(&game) {
+    ///         game.play_move(position).unwrap();
+    ///         assert_eq!(game.diagnose(), vec![]);
+    ///         assert!(STTT::is_reachable(&game.board()));
+    ///     }
+    /// }
+    ///
+    /// // X has tiles 0, 1 and 2 but board 0 is still valid
+    /// let mut code = [0; 23];
+    /// code[0] = 0b010101;
+    /// code[20] = 0b1000;
+    /// let game = STTT::decode(&code).unwrap();
+    /// assert_eq!(game.diagnose(), vec![
+    ///     StateProblem::PieceImbalance(3),
+    ///     StateProblem::WonBoardStillActive(0),
+    /// ]);
+    /// ```
+    pub fn diagnose(&self) -> Vec<StateProblem> {
+        let mut res = Vec::new();
+
+        let (x_count, o_count) = self.moves_played();
+        let imbalance = x_count as i32 - o_count as i32;
+        // Decoded games have no history and are assumed to be started by X
+        let first_move = match self.history.first() {
+            Some(&(Player::O, _)) => -1,
+            _ => 1,
+        };
+        if imbalance != 0 && imbalance != first_move {
+            res.push(StateProblem::PieceImbalance(imbalance));
+        }
+
+        for board_idx in 0..9 {
+            if !STTT::owner_matches(&self.board, board_idx, self.rules.misere) {
+                res.push(StateProblem::MetaboardMismatch(board_idx));
+            }
+        }
+
+        for board_idx in self.valid_boards() {
+            if board_idx >= 9 {
+                res.push(StateProblem::IllegalValidBoard(board_idx));
+            } else if !self.is_open(board_idx) {
+                res.push(StateProblem::WonBoardStillActive(board_idx));
+            }
+        }
+
+        res
+    }
+
+    /// Returns the legal move that leaves the opponent with the fewest legal
+    /// replies, preferring the lowest absolute position among equally good moves.
+    /// A move that ends the game leaves no replies, and one that lets the
+    /// player choose the opponent's board with `SendToClosed::OpponentChooses`
+    /// leaves those of the most crowded open board.
+    ///
+    /// Returns `None` if there are no legal moves, e.g. while a board choice
+    /// is pending, which `STTT::greedy_restrict_board` makes instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// for &(player, pos) in &[(Player::X, 40), (Player::O, 39), (Player::X, 31),
+    ///                         (Player::O, 38), (Player::X, 22), (Player::O, 42)] {
+    ///     game.play(player, Position::from_absolute(pos).unwrap()).unwrap();
+    /// }
+    ///
+    /// // X must play in board 6: tile 4 sends O to the crowded board 4,
+    /// // while every other tile sends O to a board with more free tiles
+    /// assert_eq!(game.greedy_restrict_move().unwrap().tile_idx(), 4);
+    /// ```
+    pub fn greedy_restrict_move(&self) -> Option<Position> {
+        let mut best: Option<(Position, usize)> = None;
+
+        for (position, board) in self.successor_boards() {
+            let replies = if board.winner().is_some() {
+                0
+            } else if self.rules.is_open(&board, position.tile_idx()) {
+                board.empty_tiles(position.tile_idx())
+            } else {
+                let open = (0..9)
+                    .filter(|&board_idx| self.rules.is_open(&board, board_idx))
+                    .map(|board_idx| board.empty_tiles(board_idx));
+                match self.rules.send_to_closed {
+                    SendToClosed::FreeChoice => open.sum(),
+                    SendToClosed::OpponentChooses => open.min().unwrap_or(0),
+                }
+            };
+
+            match best {
+                Some((_, fewest)) if fewest <= replies => {},
+                _ => best = Some((position, replies)),
+            }
+        }
+
+        best.map(|(position, _)| position)
+    }
+
+    /// Returns the open board with the fewest empty tiles, preferring the
+    /// lowest index, for the player who must choose where the opponent plays.
+    ///
+    /// Returns `None` if no choice is pending (see `STTT::pending_choice`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::STTT;
+    ///
+    /// // Makes the next decision, whether a move or a board choice
+    /// fn step(game: &mut STTT) {
+    ///     match game.pending_choice() {
+    ///         Some(chooser) => game.choose_board(chooser, game.greedy_restrict_board().unwrap()).unwrap(),
+    ///         None => { game.play_move(game.greedy_restrict_move().unwrap()).unwrap(); },
+    ///     }
+    /// }
+    ///
+    /// let mut game = STTT::new();
+    /// step(&mut game);
+    /// assert_eq!(game.greedy_restrict_board(), None);
+    /// ```
+    pub fn greedy_restrict_board(&self) -> Option<usize> {
+        self.pending_choice()?;
+
+        (0..9)
+            .filter(|&board_idx| self.is_open(board_idx))
+            .min_by_key(|&board_idx| self.board.empty_tiles(board_idx))
+    }
+
+    /// Makes player play at a given position.
+    ///
+    /// Returns the game `Status` resulting from this play in case of success.
+    ///
+    /// The next player to make a move swaps at each successful call to this function,
+    /// unless the move wins the game.
+    ///
+    /// The game is tied when nobody won and no board is left to play in. Being
+    /// sent to a closed board is not a tie while other boards are open.
+    ///
+    /// # Errors
+    ///
+    /// This function returns a `PlayError` if the game is already over (`GameOver`),
+    /// if a player plays in the other's turn (`NotYourTurn`), in a board that is
+    /// not valid (`BoardNotAllowed`) or in a tile that was already played
+    /// (`CellOccupied`). The game is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, PlayError};
+    ///
+    /// let p1 = Position::from_absolute(0).unwrap();
+    /// let p2 = Position::from_absolute(1).unwrap();
+    /// let p3 = Position::from_absolute(9).unwrap();
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, p1).unwrap();
+    /// assert_eq!(game.play(Player::X, p2).err(), Some(PlayError::NotYourTurn));
+    /// assert_eq!(game.play(Player::O, p3).err(), Some(PlayError::BoardNotAllowed));
+    /// assert_eq!(game.play(Player::O, p1).err(), Some(PlayError::CellOccupied));
+    /// game.play(Player::O, p2).unwrap();
+    /// game.play(Player::X, p3).unwrap();
+    /// ```
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, Status};
+    ///
+    /// // boards 0 to 6 are won without a metaboard line, O must play in board 8
+    /// let code: String = "XOXXOOO".chars()
+    ///     .map(|owner| format!("{}{}{}......", owner, owner, owner))
+    ///     .collect();
+    /// let mut game = STTT::from_code(&format!("{}{}O8", code, ".".repeat(18))).unwrap();
+    ///
+    /// // O sends X to the closed board 0, but boards 7 and 8 are still open
+    /// let status = game.play(Player::O, Position::from_relative(8, 0).unwrap());
+    /// assert_eq!(status, Ok(Status::InProgress));
+    /// assert_eq!(game.player(), Player::X);
+    /// assert_eq!(game.valid_boards(), vec![7, 8]);
+    /// assert_eq!(game.valid_moves().len(), 17);
+    /// ```
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, PlayError};
+    ///
+    /// // X won the top row of the metaboard
+    /// let mut game = STTT::from_code(&format!("{}{}X", "XXX......".repeat(3), ".".repeat(54))).unwrap();
+    ///
+    /// let pos = Position::from_absolute(40).unwrap();
+    /// assert_eq!(game.play(Player::O, pos), Err(PlayError::GameOver));
+    /// assert_eq!(game.board().get(4, 4), None);
+    /// ```
+    pub fn play(&mut self, player: Player, position: Position) -> Result<Status, PlayError> {
+        // Step 1: Check if valid play
+        if self.is_over() {
+            return Err(PlayError::GameOver);
+        }
+        if player != self.player {
+            return Err(PlayError::NotYourTurn);
+        }
+        if !self.valid_boards.contains(&position.board_idx()) {
+            return Err(PlayError::BoardNotAllowed);
+        }
+
+        // Step 2: Play the given move
+        let board_idx = position.board_idx();
+        let was_won = self.board.metaboard()[board_idx].is_some();
+
+        self.rules.play(&mut self.board, self.player, position)
+            .map_err(|_| PlayError::CellOccupied)?;
+        self.history.push((player, position));
+        self.zobrist ^= zobrist::DEFAULT.tile_key(player, position);
+
+        if let (false, Some(winner)) = (was_won, self.board.metaboard()[board_idx]) {
+            if let Some(callback) = self.on_subboard_won.as_mut() {
+                callback(board_idx, winner);
+            }
+        }
+
+        // Step 3: Check winner, who may be the opponent with `RuleSet::misere`
+        if let Some(winner) = self.board.winner() {
+            self.update_valid_boards(Some(position));
+            return Ok(Status::Winner(winner));
+        }
+
+        // Step 4: Prepare next move
+        self.update_valid_boards(Some(position));
+        self.player = self.player.opponent();
+        self.zobrist ^= zobrist::DEFAULT.side_key();
+
+        // Step 5: Check tie, i.e. the next player has no board to play in
+        Ok(self.status())
+    }
+
+    /// Makes the player whose turn it is play at a given position.
+    ///
+    /// Same as `play`, without having to pass `self.player()` in.
+    ///
+    /// # Errors
+    ///
+    /// Same as `play`, except that `NotYourTurn` can't happen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let position = Position::from_absolute(40).unwrap();
+    ///
+    /// let mut explicit = STTT::new();
+    /// let mut implicit = STTT::new();
+    /// assert_eq!(implicit.play_move(position), explicit.play(Player::X, position));
+    /// assert_eq!(implicit.encode(), explicit.encode());
+    /// ```
+    pub fn play_move(&mut self, position: Position) -> Result<Status, PlayError> {
+        self.play(self.player, position)
+    }
+
+
+    /// Plays the given moves in order, each by the player whose turn it is.
+    ///
+    /// Returns the `Status` after the last move, or the current status if no
+    /// moves are given.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first illegal move and returns its `PlayError`. The moves
+    /// before it remain played.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, PlayError, Status};
+    ///
+    /// let moves: Vec<Position> = [40, 36, 4].iter()
+    ///     .map(|&i| Position::from_absolute(i).unwrap())
+    ///     .collect();
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.replay(&moves), Ok(Status::InProgress));
+    /// assert_eq!(game.player(), Player::O);
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.replay(&[moves[0], moves[0]]), Err(PlayError::CellOccupied));
+    /// assert_eq!(game.history().len(), 1);
+    /// ```
+    pub fn replay(&mut self, moves: &[Position]) -> Result<Status, PlayError> {
+        let mut status = self.status();
+
+        for &position in moves {
+            status = self.play_move(position)?;
+        }
+
+        Ok(status)
+    }
+
+    /// Returns the player who must choose the board the next player plays
+    /// in, after the next player was sent to a closed board with
+    /// `SendToClosed::OpponentChooses`, or `None` if no choice is pending.
+    ///
+    /// The chooser is the opponent of `STTT::player`, who sent them there.
+    /// While a choice is pending the game is in progress, but there are no
+    /// valid boards nor moves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, RuleSet, SendToClosed};
+    ///
+    /// let rules = RuleSet { send_to_closed: SendToClosed::OpponentChooses, ..RuleSet::default() };
+    /// let mut game = STTT::with_rules(rules);
+    /// assert_eq!(game.pending_choice(), None);
+    ///
+    /// // O wins board 0, then X sends O there
+    /// for &pos in &[0, 2, 18, 4, 36, 6, 54] {
+    ///     game.play_move(Position::from_absolute(pos).unwrap()).unwrap();
+    /// }
+    /// assert_eq!(game.pending_choice(), Some(Player::X));
+    /// assert_eq!(game.player(), Player::O);
+    /// assert!(!game.is_over());
+    /// assert!(game.valid_moves().is_empty());
+    ///
+    /// game.choose_board(Player::X, 3).unwrap();
+    /// assert_eq!(game.pending_choice(), None);
+    /// ```
+    pub fn pending_choice(&self) -> Option<Player> {
+        if self.valid_boards.is_empty() && self.status() == Status::InProgress {
+            Some(self.player.opponent())
+        } else {
+            None
+        }
+    }
+
+    /// Makes `player` pick the board the next player must play in, after
+    /// sending them to a closed board with `SendToClosed::OpponentChooses`.
+    ///
+    /// Undoing the move after the choice undoes the choice too, and undoing
+    /// the move that sent the player to a closed board requires choosing
+    /// again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BoardNotAllowed` if no choice is pending or the board is not
+    /// open, and `NotYourTurn` if `player` is not the one to choose (see
+    /// `STTT::pending_choice`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, PlayError, RuleSet, SendToClosed};
+    ///
+    /// let rules = RuleSet { send_to_closed: SendToClosed::OpponentChooses, ..RuleSet::default() };
+    /// let mut game = STTT::with_rules(rules);
+    ///
+    /// // nobody was sent to a closed board yet
+    /// assert_eq!(game.choose_board(Player::O, 3), Err(PlayError::BoardNotAllowed));
+    ///
+    /// game.play_move(Position::from_absolute(40).unwrap()).unwrap();
+    /// assert_eq!(game.choose_board(Player::X, 3), Err(PlayError::BoardNotAllowed));
+    /// ```
+    pub fn choose_board(&mut self, player: Player, board_idx: usize) -> Result<(), PlayError> {
+        let chooser = self.pending_choice().ok_or(PlayError::BoardNotAllowed)?;
+        if player != chooser {
+            return Err(PlayError::NotYourTurn);
+        }
+        if board_idx >= 9 || !self.is_open(board_idx) {
+            return Err(PlayError::BoardNotAllowed);
+        }
+
+        self.valid_boards.insert(board_idx);
+        Ok(())
+    }
+
+    /// Reverts the last move, restoring the game to the state it had before it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlayError::NothingToUndo` if no move was played.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position, PlayError};
+    ///
+    /// let mut game = STTT::new();
+    /// assert_eq!(game.undo(), Err(PlayError::NothingToUndo));
+    ///
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    /// game.undo().unwrap();
+    /// assert_eq!(game.player(), Player::X);
+    /// assert_eq!(game.board().get(4, 4), None);
+    /// assert_eq!(game.valid_moves().len(), 81);
+    /// ```
+    ///
+    /// A won board keeps its winner while they still have a line in it, even
+    /// if the other player has one too:
+    ///
+    /// ```
+    /// use sttt::{STTT, Position, RuleSet, ai::RandomBot};
+    /// use sttt::Player::{X, O};
+    ///
+    /// let rules = RuleSet { play_in_won_boards: true, ..RuleSet::default() };
+    /// let mut game = STTT::with_rules(rules);
+    /// let mut bot = RandomBot::new(3);
+    /// while game.history().len() < 45 {
+    ///     game.play_move(bot.choose(&game).unwrap()).unwrap();
+    /// }
+    ///
+    /// // O won board 7 on the bottom row before X completed the middle one
+    /// assert_eq!(game.board().sub_board(7), [Some(X), None, Some(X),
+    ///                                        Some(X), Some(X), Some(X),
+    ///                                        Some(O), Some(O), Some(O)]);
+    /// assert_eq!(game.last_move(), Some((X, Position::from_relative(7, 2).unwrap())));
+    /// assert_eq!(game.board().metaboard()[7], Some(O));
+    ///
+    /// game.undo().unwrap();
+    /// assert_eq!(game.board().metaboard()[7], Some(O));
+    /// ```
+    pub fn undo(&mut self) -> Result<(), PlayError> {
+        let (player, position) = self.history.pop().ok_or(PlayError::NothingToUndo)?;
+
+        self.rules.clear(&mut self.board, position);
+        self.zobrist ^= zobrist::DEFAULT.tile_key(player, position);
+        if self.player != player {
+            self.zobrist ^= zobrist::DEFAULT.side_key();
+        }
+        self.player = player;
+
+        let previous = self.last_move().map(|(_, position)| position);
+        self.update_valid_boards(previous);
+
+        Ok(())
+    }
+
+    /// Computes the boards the next player can play in, after the given move
+    fn update_valid_boards(&mut self, last_move: Option<Position>) {
+        self.valid_boards.clear();
+
+        // Nobody plays after the game is won
+        if self.board.winner().is_some() {
+            return;
+        }
+
+        match last_move {
+            // Play in corresponding board if open
+            Some(position) if self.is_open(position.tile_idx()) => {
+                self.valid_boards.insert(position.tile_idx());
+            },
+            // Otherwise wait for the opponent to choose...
+            Some(_) if self.rules.send_to_closed == SendToClosed::OpponentChooses => {},
+            // ...or play in every available board
+            _ => {
+                for board in 0..9 {
+                    if self.is_open(board) {
+                        self.valid_boards.insert(board);
+                    }
+                }
+            },
+        }
+    }
+
+    /// Returns the moves of the history with how they were played, undoing
+    /// them on a copy of the game, so that games set up from a position
+    /// are covered too
+    fn move_records(&self) -> Vec<MoveRecord> {
+        let mut game = self.clone();
+        let mut res = Vec::with_capacity(self.history.len());
+
+        let mut after = self.board;
+        while let Some((player, position)) = game.last_move() {
+            game.undo().unwrap();
+
+            let board_idx = position.board_idx();
+            res.push(MoveRecord {
+                player,
+                position,
+                // No valid boards means the opponent chose this one
+                forced: game.valid_boards.len() <= 1,
+                captured: game.board.metaboard()[board_idx].is_none()
+                    && after.metaboard()[board_idx].is_some(),
+                board: after,
+            });
+            after = game.board;
+        }
+
+        res.reverse();
+        res
+    }
+
+    /// Gives each board won by a single player to their opponent, as
+    /// `RuleSet::misere` does, for boards set up from their tiles
+    fn hand_over_boards(board: &mut Board) {
+        for board_idx in 0..9 {
+            if let (false, Some(winner)) = (board.is_contested(board_idx), board.metaboard()[board_idx]) {
+                board.set_owner(board_idx, winner.opponent());
+            }
+        }
+    }
+
+    /// Returns `true` if the winner of the given small board has a line in
+    /// it, or its opponent does with `RuleSet::misere`, or nobody does if it
+    /// has no winner. With `RuleSet::play_in_won_boards`, the other player
+    /// may have a line too.
+    fn owner_matches(board: &Board, board_idx: usize, misere: bool) -> bool {
+        let small_board = board.sub_board(board_idx);
+
+        match board.metaboard()[board_idx] {
+            Some(owner) if misere => STTT::has_line(&small_board, owner.opponent()),
+            Some(owner) => STTT::has_line(&small_board, owner),
+            None => Board::check_winner(&small_board).is_none(),
+        }
+    }
+
+    fn has_line(board: &[Option<Player>; 9], player: Player) -> bool {
+        let mut only_player = [None; 9];
+        for (tile, &other) in only_player.iter_mut().zip(board.iter()) {
+            if other == Some(player) {
+                *tile = other;
+            }
+        }
+        Board::check_winner(&only_player).is_some()
+    }
+}
+
+impl fmt::Display for STTT {
+    /// Displays the board, marking the boards the next player can play in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player, Position};
+    ///
+    /// let mut game = STTT::new();
+    /// game.play(Player::X, Position::from_absolute(40).unwrap()).unwrap();
+    ///
+    /// let rendered = game.to_string();
+    /// let lines: Vec<&str> = rendered.lines().collect();
+    /// assert_eq!(lines[0], "               |               |");
+    /// assert_eq!(lines[8], "               |  ***********  |");
+    /// assert_eq!(lines[14], "               |  ***********  |");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let highlighted = match self.status() {
+            Status::InProgress => self.valid_boards(),
+            _ => Vec::new(),
+        };
+        write!(f, "{}", self.board.render_with_highlight(&highlighted))
+    }
+}
+
+impl Clone for STTT {
+    fn clone(&self) -> Self {
+        STTT {
+            player: self.player,
+            board: self.board,
+            valid_boards: self.valid_boards.clone(),
+            history: self.history.clone(),
+            rules: self.rules,
+            zobrist: self.zobrist,
+            on_subboard_won: None,
+        }
+    }
+}
+
+impl Default for STTT {
+    /// Same as `STTT::new()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sttt::{STTT, Player};
+    ///
+    /// let game = STTT::default();
+    /// assert!(game.board() == STTT::new().board());
+    /// assert_eq!(game.player(), Player::X);
+    /// assert_eq!(game.valid_boards(), STTT::new().valid_boards());
+    /// ```
+    fn default() -> Self { STTT::new() }
+}
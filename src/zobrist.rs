@@ -0,0 +1,100 @@
+use super::{Board, Player, Position};
+
+/// Seed of the keys used by `ZobristHasher::new`
+const DEFAULT_SEED: u64 = 0x2F6B_D1A4_93C8_E705;
+
+/// Zobrist keys for hashing positions: one random key per tile and player,
+/// and one for `O` being the next to play.
+///
+/// The hash of a position is the XOR of the keys of its played tiles, and
+/// of the side key if `O` is next. Playing or undoing a move only takes
+/// XORing the changed keys, which is how `STTT::zobrist` keeps its hash.
+///
+/// Which boards are valid to play in is not part of the hash.
+///
+/// # Examples
+///
+/// ```
+/// use sttt::{STTT, Player, Position, ZobristHasher};
+///
+/// let hasher = ZobristHasher::new();
+/// let pos = Position::from_absolute(40).unwrap();
+///
+/// let mut game = STTT::new();
+/// assert_eq!(game.zobrist(), 0);
+/// game.play(Player::X, pos).unwrap();
+/// assert_eq!(game.zobrist(), hasher.tile_key(Player::X, pos) ^ hasher.side_key());
+/// assert_eq!(game.zobrist(), hasher.hash(&game.board(), game.player()));
+/// ```
+#[derive(Clone)]
+pub struct ZobristHasher {
+    tiles: [[u64; 81]; 2],
+    side: u64,
+}
+
+/// Keys generated from `DEFAULT_SEED`, shared by every game
+pub(crate) const DEFAULT: ZobristHasher = ZobristHasher::with_seed(DEFAULT_SEED);
+
+impl ZobristHasher {
+    /// Creates a hasher with the keys `STTT::zobrist` uses.
+    pub const fn new() -> ZobristHasher {
+        DEFAULT
+    }
+
+    /// Creates a hasher with keys generated from the given seed.
+    pub const fn with_seed(seed: u64) -> ZobristHasher {
+        let mut state = seed;
+        let mut tiles = [[0; 81]; 2];
+
+        let mut player = 0;
+        while player < 2 {
+            let mut pos = 0;
+            while pos < 81 {
+                state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                tiles[player][pos] = splitmix64(state);
+                pos += 1;
+            }
+            player += 1;
+        }
+
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        ZobristHasher {
+            tiles,
+            side: splitmix64(state),
+        }
+    }
+
+    /// Returns the key of `player` having played at `position`
+    pub fn tile_key(&self, player: Player, position: Position) -> u64 {
+        self.tiles[player as usize][position.to_absolute()]
+    }
+
+    /// Returns the key of `O` being the next to play
+    pub fn side_key(&self) -> u64 {
+        self.side
+    }
+
+    /// Hashes a position from scratch, given its board and next player.
+    pub fn hash(&self, board: &Board, next: Player) -> u64 {
+        let mut res = if next == Player::O { self.side } else { 0 };
+
+        for (position, tile) in board.cells() {
+            if let Some(player) = tile {
+                res ^= self.tile_key(player, position);
+            }
+        }
+
+        res
+    }
+}
+
+impl Default for ZobristHasher {
+    fn default() -> Self { ZobristHasher::new() }
+}
+
+/// Mixes a counter into a well distributed 64 bit value
+const fn splitmix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,120 @@
+//! Searches and bots on fixed games.
+
+mod common;
+
+use common::{game, game_with_rules, pos, X_WINS, O_SENT_TO_WON_BOARD, TIE};
+use sttt::{STTT, Player, PlayError, RuleSet, SendToClosed, ai};
+
+fn opponent_chooses() -> RuleSet {
+    RuleSet { send_to_closed: SendToClosed::OpponentChooses, ..RuleSet::default() }
+}
+
+#[test]
+fn winning_moves_complete_a_metaboard_line() {
+    let before = game(&X_WINS[..16]);
+    assert_eq!(&before.board().metaboard()[..3], &[Some(Player::X), Some(Player::X), None]);
+    assert_eq!(ai::winning_moves(&before), vec![pos(22)]);
+
+    assert_eq!(ai::winning_moves(&game(&X_WINS[..15])), vec![]);
+    assert_eq!(ai::winning_moves(&game(&X_WINS)), vec![]);
+}
+
+#[test]
+fn best_move_wins_the_game() {
+    let before = game(&X_WINS[..16]);
+    assert_eq!(ai::best_move(&before, 2), Some(pos(22)));
+    assert_eq!(ai::best_move(&game(&X_WINS), 2), None);
+}
+
+#[test]
+fn shortest_mate_finds_a_mate_in_one() {
+    assert_eq!(ai::shortest_mate(&game(&X_WINS[..16]), 3), Some(1));
+    assert_eq!(ai::shortest_mate(&game(&X_WINS), 3), None);
+}
+
+#[test]
+fn all_mating_moves_accepts_every_win() {
+    // X owns boards 0 and 1, and completes board 2 at either 20 or 24
+    let position = STTT::from_code(&format!("XXX......XXX......XX.X.....{}X", ".".repeat(54))).unwrap();
+    assert_eq!(ai::shortest_mate(&position, 1), Some(1));
+    assert_eq!(ai::all_mating_moves(&position, 1), vec![pos(20), pos(24)]);
+}
+
+#[test]
+fn all_moves_losing_spots_lost_positions() {
+    // O must play the last tile of board 8, sending X to board 2, where X
+    // completes the top row of the metaboard
+    let lost = STTT::from_code(&format!("XXX......XXX......XX.......{}XO.OXXXOOO8", ".".repeat(45))).unwrap();
+    assert_eq!(lost.valid_moves(), vec![pos(74)]);
+    assert!(lost.all_moves_losing(2));
+    // the loss is only seen two moves ahead
+    assert!(!lost.all_moves_losing(1));
+
+    assert!(!game(&[]).all_moves_losing(2));
+    assert!(!game(&X_WINS).all_moves_losing(2));
+}
+
+#[test]
+fn constraint_after_best_reply_plays_both_moves() {
+    let position = game(&X_WINS[..16]);
+
+    // there is no reply to the winning move
+    assert_eq!(position.constraint_after_best_reply(pos(22), 2), Ok(None));
+    assert!(!position.is_over());
+
+    assert_eq!(position.constraint_after_best_reply(pos(18), 2), Err(PlayError::CellOccupied));
+    assert_eq!(position.constraint_after_best_reply(pos(5), 2), Err(PlayError::BoardNotAllowed));
+    assert_eq!(game(&X_WINS).constraint_after_best_reply(pos(40), 2), Err(PlayError::GameOver));
+}
+
+#[test]
+fn depth_zero_searches_like_depth_one() {
+    for before in &[game(&[]), game(&X_WINS[..16])] {
+        assert_eq!(ai::best_move(before, 0), ai::best_move(before, 1));
+        assert_eq!(ai::search(before, 0, true), ai::search(before, 1, true));
+    }
+}
+
+#[test]
+fn pruning_keeps_the_best_move() {
+    for moves in &[&[40, 39, 31, 38, 22, 42][..], &O_SENT_TO_WON_BOARD[..], &TIE[..10]] {
+        let position = game(moves);
+
+        let pruned = ai::search(&position, 3, true);
+        let full = ai::search(&position, 3, false);
+        assert_eq!(pruned.best, full.best);
+        assert!(pruned.nodes < full.nodes);
+    }
+}
+
+#[test]
+fn best_board_is_chosen_while_a_choice_is_pending() {
+    let mut position = game_with_rules(&O_SENT_TO_WON_BOARD, opponent_chooses());
+    assert_eq!(ai::best_move(&position, 2), None);
+
+    let board_idx = ai::best_board(&position, 2).unwrap();
+    position.choose_board(Player::X, board_idx).unwrap();
+    assert_eq!(ai::best_board(&position, 2), None);
+    assert!(ai::best_move(&position, 2).is_some());
+}
+
+#[test]
+fn perft_branches_on_board_choices() {
+    let position = game_with_rules(&O_SENT_TO_WON_BOARD, opponent_chooses());
+
+    // X may send O to boards 2, 4 and 6 with 8 empty tiles, or to the five
+    // others with 9
+    assert_eq!(ai::perft(&position, 1), 3 * 8 + 5 * 9);
+    assert_eq!(ai::perft(&game(&O_SENT_TO_WON_BOARD), 1), 3 * 8 + 5 * 9);
+}
+
+#[test]
+fn cell_importance_is_highest_on_the_winning_move() {
+    let importance = game(&X_WINS[..16]).cell_importance();
+
+    let best = (0..81).max_by(|&a, &b| importance[a].partial_cmp(&importance[b]).unwrap());
+    assert_eq!(best, Some(22));
+    // occupied, and in a won board
+    assert_eq!(importance[17], 0.0);
+    assert_eq!(importance[5], 0.0);
+}
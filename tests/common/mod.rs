@@ -0,0 +1,39 @@
+//! Games shared by the integration tests, as absolute positions.
+
+#![allow(dead_code)]
+
+use sttt::{STTT, Position, RuleSet};
+
+/// X wins boards 0 and 1, then board 2 and the game with the last move, at
+/// 22, which X was free to play
+pub const X_WINS: [usize; 17] = [17, 72, 7, 63, 4, 37, 13, 36, 1, 11, 26, 74, 18, 27, 9, 64, 22];
+
+/// O wins board 0 on its anti-diagonal with the sixth move, at 6, then X
+/// sends O back there
+pub const O_SENT_TO_WON_BOARD: [usize; 7] = [0, 2, 18, 4, 36, 6, 54];
+
+/// Every board is closed without a metaboard line, the last one at 31
+pub const TIE: [usize; 31] = [76, 42, 62, 78, 58, 38, 19, 17, 72, 8, 80, 40, 54, 2, 22, 5,
+                              50, 45, 25, 71, 11, 69, 35, 70, 15, 46, 13, 47, 27, 28, 31];
+
+/// Returns the position with the given absolute index
+pub fn pos(i: usize) -> Position {
+    Position::from_absolute(i).unwrap()
+}
+
+/// Returns the positions with the given absolute indices
+pub fn positions(moves: &[usize]) -> Vec<Position> {
+    moves.iter().map(|&i| pos(i)).collect()
+}
+
+/// Plays the given moves in a new game
+pub fn game(moves: &[usize]) -> STTT {
+    game_with_rules(moves, RuleSet::default())
+}
+
+/// Plays the given moves in a new game with the given rules
+pub fn game_with_rules(moves: &[usize], rules: RuleSet) -> STTT {
+    let mut game = STTT::with_rules(rules);
+    game.replay(&positions(moves)).unwrap();
+    game
+}
@@ -0,0 +1,244 @@
+//! Whole games played with the default rules.
+
+mod common;
+
+use common::{game, pos, positions, X_WINS, O_SENT_TO_WON_BOARD, TIE};
+use sttt::{STTT, CellLegality, Player, PlayError, Status, ai::RandomBot};
+
+/// Plays random moves until the game is over
+fn random_game(seed: u64) -> STTT {
+    let mut bot = RandomBot::new(seed);
+    let mut game = STTT::new();
+    while let Some(position) = bot.choose(&game) {
+        game.play_move(position).unwrap();
+    }
+    game
+}
+
+#[test]
+fn status_follows_the_metaboard() {
+    assert_eq!(game(&X_WINS[..16]).status(), Status::InProgress);
+    assert_eq!(game(&X_WINS).status(), Status::Winner(Player::X));
+    assert_eq!(game(&TIE).status(), Status::Tie);
+
+    let won = game(&X_WINS);
+    assert_eq!(won.winner(), Some(Player::X));
+    assert!(won.is_over());
+    assert_eq!(won.board().criticality(), 1.0);
+    assert_eq!(won.winning_line(), Some([0, 1, 2]));
+    assert_eq!(game(&X_WINS[..16]).winning_line(), None);
+
+    let tied = game(&TIE);
+    assert_eq!(tied.winner(), None);
+    assert!(tied.is_over());
+    assert_eq!(tied.board().criticality(), 1.0);
+
+    let in_progress = game(&X_WINS[..3]);
+    assert_eq!(in_progress.winner(), None);
+    assert!(!in_progress.is_over());
+}
+
+#[test]
+fn the_last_move_of_a_tie_leaves_no_moves() {
+    let mut tied = game(&TIE[..30]);
+    assert_eq!(tied.play(Player::X, pos(31)), Ok(Status::Tie));
+    assert!(tied.valid_moves().is_empty());
+}
+
+#[test]
+fn nothing_is_played_after_a_win() {
+    let mut won = game(&X_WINS);
+
+    let board = won.board();
+    for &player in &[Player::X, Player::O] {
+        assert_eq!(won.play(player, pos(40)), Err(PlayError::GameOver));
+        assert_eq!(won.play_move(pos(40)), Err(PlayError::GameOver));
+    }
+    assert!(won.board() == board);
+    assert_eq!(won.history().len(), 17);
+    assert_eq!(won.valid_boards(), vec![]);
+}
+
+#[test]
+fn a_won_board_sends_the_next_player_anywhere() {
+    let mut sent = game(&O_SENT_TO_WON_BOARD[..5]);
+    assert_eq!(sent.sub_board_winner(0), None);
+
+    // O completes the anti-diagonal of board 0, sending X to board 6
+    sent.play_move(pos(6)).unwrap();
+    assert_eq!(sent.sub_board_winner(0), Some(Player::O));
+    assert_eq!(sent.sub_board_winner(6), None);
+    assert_eq!(sent.valid_boards(), vec![6]);
+    assert_eq!(sent.forced_board(), Some(6));
+
+    let legality = sent.cell_legality();
+    assert_eq!(legality[0], CellLegality::Occupied);
+    assert_eq!(legality[1], CellLegality::BoardDecided);
+    assert_eq!(legality[27], CellLegality::WrongBoard);
+    assert_eq!(legality[54], CellLegality::Legal);
+    assert_eq!(legality.iter().filter(|&&l| l == CellLegality::Legal).count(), 9);
+
+    // X sends O to the closed board 0, so O can play in any open board
+    sent.play(Player::X, pos(54)).unwrap();
+    assert_eq!(sent.valid_boards(), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(sent.forced_board(), None);
+    assert!(!sent.is_open(0));
+
+    let rendered = sent.to_string();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[0], "               |  ***********  |  ***********");
+    assert_eq!(lines[8], "  ***********  |  ***********  |  ***********");
+}
+
+#[test]
+fn undo_gives_a_won_board_back() {
+    let mut undone = game(&O_SENT_TO_WON_BOARD[..6]);
+    assert_eq!(undone.board().metaboard()[0], Some(Player::O));
+
+    undone.undo().unwrap();
+    assert_eq!(undone.board().metaboard()[0], None);
+    assert_eq!(undone.player(), Player::O);
+    assert_eq!(undone.valid_boards(), vec![0]);
+    assert_eq!(undone.history().len(), 5);
+}
+
+#[test]
+fn moves_are_counted_and_alternate() {
+    let mut counted = STTT::new();
+    for (n, position) in positions(&X_WINS).into_iter().enumerate() {
+        // X plays the even moves and O the odd ones
+        let expected = if n % 2 == 0 { Player::X } else { Player::O };
+        assert_eq!(counted.player(), expected);
+
+        counted.play_move(position).unwrap();
+        assert_eq!(counted.move_count(), n + 1);
+
+        // a second move by the same player is rejected and not counted
+        assert!(counted.play(expected, position).is_err());
+        assert_eq!(counted.move_count(), n + 1);
+    }
+    assert_eq!(STTT::from_code(&counted.to_code()).unwrap().move_count(), 17);
+}
+
+#[test]
+fn play_move_plays_for_the_next_player() {
+    let moves = positions(&X_WINS);
+
+    let mut explicit = STTT::new();
+    let mut implicit = STTT::new();
+    for &position in moves.iter().chain(moves.iter().take(1)) {
+        let player = explicit.player();
+        assert_eq!(implicit.play_move(position), explicit.play(player, position));
+        assert_eq!(implicit.encode(), explicit.encode());
+    }
+}
+
+#[test]
+fn replay_stops_at_the_first_illegal_move() {
+    let mut replayed = STTT::new();
+    assert_eq!(replayed.replay(&positions(&X_WINS)), Ok(Status::Winner(Player::X)));
+
+    let mut replayed = STTT::new();
+    assert_eq!(replayed.replay(&positions(&[17, 17])), Err(PlayError::BoardNotAllowed));
+    assert_eq!(replayed.history().len(), 1);
+}
+
+#[test]
+fn codes_round_trip() {
+    let played = game(&X_WINS[..9]);
+    let parsed = STTT::from_code(&played.to_code()).unwrap();
+    assert_eq!(parsed.encode(), played.encode());
+    assert_eq!(STTT::from_code(&played.to_code()).unwrap().history(), &[]);
+    assert_eq!(STTT::from_code(&STTT::new().to_code()).unwrap().encode(), STTT::new().encode());
+
+    let copy = STTT::from_moves(&played.history_positions()).unwrap();
+    assert_eq!(copy.encode(), played.encode());
+    assert_eq!(copy.history(), played.history());
+}
+
+#[test]
+fn games_won_with_a_forced_move_round_trip() {
+    let won = random_game(1);
+    assert!(won.winner().is_some());
+    assert_eq!(won.to_code().len(), 82);
+
+    let parsed = STTT::from_code(&won.to_code()).unwrap();
+    assert_eq!(parsed.encode(), won.encode());
+    assert_eq!(parsed.status(), won.status());
+
+    let snapshot = won.snapshot();
+    assert_eq!(snapshot.valid_boards, vec![]);
+    assert!(snapshot.to_json().contains(r#""valid_boards":[],"status":"won""#));
+}
+
+#[test]
+fn snapshots_follow_the_game() {
+    let mut played = STTT::new();
+    for &i in &O_SENT_TO_WON_BOARD[..6] {
+        played.play_move(pos(i)).unwrap();
+
+        let snapshot = played.snapshot();
+        let mut boards: Vec<usize> = played.valid_moves().iter().map(|p| p.board_idx()).collect();
+        boards.dedup();
+        assert_eq!(snapshot.valid_boards, boards);
+        assert_eq!(snapshot.cells[i], played.last_move().map(|(player, _)| player));
+    }
+
+    let snapshot = played.snapshot();
+    assert_eq!(snapshot.metaboard[0], Some(Player::O));
+    assert_eq!(snapshot.player, Player::X);
+    assert!(snapshot.to_json().contains(r#""valid_boards":[6]"#));
+}
+
+#[test]
+fn reviews_note_forced_moves_and_captures() {
+    let mut reviewed = game(&O_SENT_TO_WON_BOARD);
+    reviewed.play_move(pos(27)).unwrap();
+
+    // O completes the anti-diagonal of board 0 with the sixth move
+    assert_eq!(reviewed.capturing_move(0), Some((pos(6), 6)));
+    assert_eq!(reviewed.capturing_move(6), None);
+    assert_eq!(STTT::from_code(&reviewed.to_code()).unwrap().capturing_move(0), None);
+
+    let transcript = reviewed.transcript();
+    let lines: Vec<&str> = transcript.lines().collect();
+    assert_eq!(lines[0], "1. X 0:0 free");
+    assert_eq!(lines[1], "2. O 0:2 forced");
+    assert_eq!(lines[5], "6. O 0:6 forced, captures board 0");
+    // X sent O to the board O had just won
+    assert_eq!(lines[7], "8. O 3:0 free");
+    assert_eq!(lines.len(), 8);
+}
+
+#[test]
+fn random_positions_are_legal_and_reproducible() {
+    for &target_moves in &[0, 1, 20, 40] {
+        let position = STTT::random_position(target_moves, &mut RandomBot::new(7));
+        assert_eq!(position.move_count(), target_moves);
+        assert!(position.diagnose().is_empty());
+
+        let again = STTT::random_position(target_moves, &mut RandomBot::new(7));
+        assert_eq!(again.encode(), position.encode());
+    }
+
+    // the game ends before every tile is filled
+    let ended = STTT::random_position(81, &mut RandomBot::new(1));
+    assert!(ended.is_over());
+    assert!(ended.move_count() < 81);
+    assert!(ended.diagnose().is_empty());
+    assert_eq!(ended.encode(), random_game(1).encode());
+}
+
+#[test]
+fn games_with_a_callback_can_move_between_threads() {
+    fn assert_send<T: Send>() {}
+    assert_send::<STTT>();
+
+    let mut watched = STTT::new();
+    watched.set_on_subboard_won(Box::new(|_, _| {}));
+    let watched = std::thread::spawn(move || {
+        watched.replay(&positions(&X_WINS)).unwrap();
+        watched
+    }).join().unwrap();
+    assert_eq!(watched.winner(), Some(Player::X));
+}
@@ -0,0 +1,48 @@
+//! Runs the `play` binary through a whole game.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+mod common;
+
+use common::{pos, X_WINS};
+use sttt::STTT;
+
+#[test]
+fn board_wins_are_announced_by_the_binary_only() {
+    // The binary announces each board won, from the metaboard
+    let mut game = STTT::new();
+    let mut expected = Vec::new();
+    for &i in &X_WINS {
+        let position = pos(i);
+        let before = game.board().metaboard()[position.board_idx()];
+        game.play_move(position).unwrap();
+        if let (None, Some(p)) = (before, game.board().metaboard()[position.board_idx()]) {
+            expected.push(format!("{} wins board {}!!", p, position.board_idx()));
+        }
+    }
+    assert_eq!(expected.len(), 3);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_play"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let input: String = X_WINS.iter().map(|pos| format!("{}\n", pos)).collect();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // The library prints to the same stdout, so any of its own announcements
+    // or debug output would show up here. Announcements follow the prompt on
+    // the same line.
+    let announced: Vec<&str> = stdout.lines()
+        .filter_map(|line| line.find(" wins board").map(|i| &line[i - 1..]))
+        .collect();
+    assert_eq!(announced, expected);
+    assert!(stdout.contains("X wins!"));
+    assert!(!stdout.contains("Valid boards"));
+}
@@ -0,0 +1,190 @@
+//! Games played with non-default `RuleSet`s.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::{game, game_with_rules, pos, positions, O_SENT_TO_WON_BOARD};
+use sttt::{STTT, CellLegality, Player, PlayError, RuleSet, SendToClosed, Status, ai, ai::RandomBot};
+
+fn opponent_chooses() -> RuleSet {
+    RuleSet { send_to_closed: SendToClosed::OpponentChooses, ..RuleSet::default() }
+}
+
+fn play_in_won_boards() -> RuleSet {
+    RuleSet { play_in_won_boards: true, ..RuleSet::default() }
+}
+
+fn misere() -> RuleSet {
+    RuleSet { misere: true, ..RuleSet::default() }
+}
+
+#[test]
+fn won_boards_stay_open_when_allowed() {
+    let standard = game(&O_SENT_TO_WON_BOARD);
+    assert!(!standard.is_open(0));
+    assert_eq!(standard.valid_boards(), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let mut open = game_with_rules(&O_SENT_TO_WON_BOARD, play_in_won_boards());
+    assert!(open.is_open(0));
+    assert!(!open.board().is_open(0));
+    assert_eq!(open.valid_boards(), vec![0]);
+
+    open.play(Player::O, pos(8)).unwrap();
+    assert_eq!(open.board().metaboard()[0], Some(Player::O));
+}
+
+#[test]
+fn the_opponent_chooses_after_a_send_to_a_closed_board() {
+    let mut chosen = game_with_rules(&O_SENT_TO_WON_BOARD, opponent_chooses());
+    assert_eq!(chosen.player(), Player::O);
+    assert_eq!(chosen.valid_boards(), vec![]);
+
+    let position = pos(27);
+    assert_eq!(chosen.play(Player::O, position), Err(PlayError::BoardNotAllowed));
+    assert_eq!(chosen.choose_board(Player::O, 3), Err(PlayError::NotYourTurn));
+    assert_eq!(chosen.choose_board(Player::X, 0), Err(PlayError::BoardNotAllowed));
+
+    chosen.choose_board(Player::X, 3).unwrap();
+    assert_eq!(chosen.valid_boards(), vec![3]);
+    assert_eq!(chosen.choose_board(Player::X, 4), Err(PlayError::BoardNotAllowed));
+    chosen.play(Player::O, position).unwrap();
+}
+
+#[test]
+fn greedy_choices_restrict_the_opponent() {
+    let mut chosen = game_with_rules(&O_SENT_TO_WON_BOARD, opponent_chooses());
+    assert_eq!(chosen.greedy_restrict_move(), None);
+
+    // boards 2, 4 and 6 have 8 empty tiles, the others 9
+    let board_idx = chosen.greedy_restrict_board().unwrap();
+    assert_eq!(board_idx, 2);
+    chosen.choose_board(Player::X, board_idx).unwrap();
+    assert_eq!(chosen.greedy_restrict_board(), None);
+    assert_eq!(chosen.greedy_restrict_move().unwrap().board_idx(), 2);
+}
+
+/// Checks that the game survives `encode` and `to_code` unchanged
+fn assert_round_trips(game: &STTT) {
+    for copy in &[STTT::decode(&game.encode()).unwrap(), STTT::from_code(&game.to_code()).unwrap()] {
+        assert_eq!(copy.encode(), game.encode());
+        assert_eq!(copy.to_code(), game.to_code());
+        assert_eq!(copy.board().metaboard(), game.board().metaboard());
+        assert_eq!(copy.rules(), game.rules());
+        assert_eq!(copy.status(), game.status());
+        assert_eq!(copy.valid_moves(), game.valid_moves());
+        assert_eq!(copy.pending_choice(), game.pending_choice());
+        assert_eq!(copy.diagnose(), vec![]);
+    }
+}
+
+#[test]
+fn contested_boards_keep_their_owner_in_codes() {
+    let mut bot = RandomBot::new(3);
+    let mut contested = STTT::with_rules(play_in_won_boards());
+    while contested.history().len() < 45 {
+        contested.play_move(bot.choose(&contested).unwrap()).unwrap();
+    }
+
+    // O won board 7 on the bottom row before X completed the middle one
+    assert_eq!(contested.board().metaboard()[7], Some(Player::O));
+    assert!(contested.to_code().ends_with("Ow"));
+    assert_round_trips(&contested);
+
+    let code = contested.to_code();
+    assert!(STTT::from_code(&code.replace("Ow", "w")).is_err());
+    assert!(STTT::from_code(&code.replace("Ow", "OXw")).is_err());
+}
+
+#[test]
+fn games_with_other_rules_round_trip() {
+    let misere_in_won_boards = RuleSet { misere: true, ..play_in_won_boards() };
+    for &rules in &[play_in_won_boards(), opponent_chooses(), misere(), misere_in_won_boards] {
+        for seed in 1..20 {
+            let mut bot = RandomBot::new(seed);
+            let mut played = STTT::with_rules(rules);
+            loop {
+                assert_round_trips(&played);
+                match (played.pending_choice(), bot.choose(&played)) {
+                    (Some(chooser), _) => played.choose_board(chooser, bot.choose_board(&played).unwrap()).unwrap(),
+                    (None, Some(position)) => { played.play_move(position).unwrap(); },
+                    (None, None) => break,
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn pending_choices_are_kept_in_codes() {
+    let pending = game_with_rules(&O_SENT_TO_WON_BOARD, opponent_chooses());
+    assert!(pending.to_code().ends_with("O-c"));
+    assert_round_trips(&pending);
+
+    // only the opponent can choose a board
+    let code = pending.to_code();
+    assert!(STTT::from_code(&code[..code.len() - 1]).is_err());
+}
+
+#[test]
+fn misere_captures_go_to_the_opponent() {
+    let mut captured = STTT::with_rules(misere());
+    let won = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&won);
+    captured.set_on_subboard_won(Box::new(move |board_idx, player| {
+        recorder.lock().unwrap().push((board_idx, player));
+    }));
+
+    // O completes the anti-diagonal of board 0, which goes to X
+    captured.replay(&positions(&O_SENT_TO_WON_BOARD[..6])).unwrap();
+    assert_eq!(captured.board().metaboard()[0], Some(Player::X));
+    assert_eq!(*won.lock().unwrap(), vec![(0, Player::X)]);
+    assert!(!captured.is_open(0));
+    assert_eq!(captured.cell_legality()[1], CellLegality::BoardDecided);
+    assert_eq!(captured.diagnose(), vec![]);
+
+    // O is sent to the decided board 0, so it can play anywhere else
+    captured.play_move(pos(54)).unwrap();
+    assert_eq!(captured.forced_board(), None);
+    assert_eq!(captured.valid_boards(), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+    captured.undo().unwrap();
+    captured.undo().unwrap();
+    assert_eq!(captured.board().metaboard()[0], None);
+    assert_eq!(captured.valid_boards(), vec![0]);
+}
+
+#[test]
+fn misere_lines_of_boards_win_for_the_opponent() {
+    // X completed the top rows of boards 0 and 1, which went to O
+    let position = STTT::from_code(&format!("XXX......XXX......XX{}Xm", ".".repeat(61))).unwrap();
+    assert_eq!(&position.board().metaboard()[..3], &[Some(Player::O), Some(Player::O), None]);
+    assert_eq!(ai::winning_moves(&position), vec![]);
+    assert!(ai::threats(&position, Player::X).contains(&pos(20)));
+
+    let mut lost = position.clone();
+    assert_eq!(lost.play_move(pos(20)), Ok(Status::Winner(Player::O)));
+    assert_eq!(lost.valid_moves(), vec![]);
+}
+
+#[test]
+fn misere_undo_restores_every_board() {
+    let misere_in_won_boards = RuleSet { misere: true, ..play_in_won_boards() };
+    for &rules in &[misere(), misere_in_won_boards] {
+        for seed in 1..20 {
+            let mut bot = RandomBot::new(seed);
+            let mut played = STTT::with_rules(rules);
+            let mut boards = vec![played.board()];
+            while let Some(position) = bot.choose(&played) {
+                played.play_move(position).unwrap();
+                assert_eq!(played.diagnose(), vec![]);
+                boards.push(played.board());
+            }
+
+            while played.undo().is_ok() {
+                boards.pop();
+                assert!(played.board() == *boards.last().unwrap());
+            }
+        }
+    }
+}